@@ -48,8 +48,14 @@ fn print_change(oper: SrChangeOper, old_val: SrValue, new_val: SrValue) {
 
 /// Print current config.
 fn print_current_config(sess: &mut SrSession, mod_name: &str) {
-    let xpath = format!("/{}:*//.", mod_name);
-    let xpath = &xpath[..];
+    let xpath = match XPathBuilder::new()
+        .step(mod_name, "*")
+        .descendant(".")
+        .build()
+    {
+        Ok(xpath) => xpath,
+        Err(_) => return,
+    };
 
     // Get the values.
     match sess.get_items(&xpath, None, 0) {
@@ -130,8 +136,7 @@ fn run() -> bool {
         println!("");
         println!(
             " ========== EVENT ({}) {} CHANGES: ====================================",
-            sub_id,
-            event
+            sub_id, event
         );
         println!("");
 
@@ -64,7 +64,7 @@ fn run() -> bool {
     };
 
     // Create the notification.
-    let notif = match LibYang::lyd_new_path(None, Some(&ly_ctx), &path, None, 0) {
+    let mut notif = match LibYang::lyd_new_path(&ly_ctx, &path, None, 0) {
         Ok(notif) => notif,
         Err(_) => {
             println!(r#"Creating notification "{}" failed."#, path);
@@ -75,24 +75,25 @@ fn run() -> bool {
     // Add the input value.
     if let Some((path, value)) = node_path_val {
         let value = LydValue::from_string(value);
-        match LibYang::lyd_new_path(Some(&notif), None, &path, Some(&value), 0) {
+        match notif.new_path(&path, Some(&value), 0) {
             Ok(_) => {}
             Err(_) => {
-                notif.free_all();
-
                 println!(r#"Creating value "{}" failed."#, path);
                 return false;
             }
         }
     }
 
+    // Validate the notification.
+    if let Err(err) = notif.validate_op(None, LydOpType::NotifYang) {
+        println!(r#"Notification "{}" is invalid: {}."#, path, err);
+        return false;
+    }
+
     // Send the notification.
     if let Err(_) = sess.notif_send_tree(&notif, 0, 0) {
-        notif.free_all();
-
         return false;
     }
 
-    notif.free_all();
     true
 }
@@ -6,6 +6,7 @@
 use std::env;
 use std::thread;
 use std::time;
+use std::time::SystemTime;
 
 use sysrepo::*;
 use utils::*;
@@ -69,13 +70,16 @@ fn run() -> bool {
              _notif_type: SrNotifType,
              path: &str,
              mut values: SrValueSlice,
-             _timestamp: *mut timespec| {
+             timestamp: Option<SystemTime>| {
+        let timestamp = timestamp
+            .and_then(|timestamp| system_time_to_date_and_time(timestamp).ok())
+            .unwrap_or_default();
+
         println!("");
         println!("");
         println!(
-            r#" ========== NOTIFICATION ({}) "{}" RECEIVED ======================="#,
-            sub_id,
-            path
+            r#" ========== NOTIFICATION ({}) "{}" RECEIVED AT {} ======================="#,
+            sub_id, path, timestamp
         );
         println!("");
 
@@ -54,13 +54,15 @@ fn run() -> bool {
     };
 
     // Callback
-    let f = |ctx: &LibYangCtx,
+    let f = |_sess: SrSession,
+             ctx: &LibYangCtx,
              sub_id: u32,
              mod_name: &str,
              path: &str,
              _request_xpath: Option<&str>,
-             _request_id: u32|
-     -> Option<LydNode> {
+             _request_id: u32,
+             parent: Option<LydNodeRef>|
+     -> Result<Option<LydNode>, SrCallbackError> {
         println!("");
         println!("");
         println!(
@@ -75,12 +77,22 @@ fn run() -> bool {
             let path2 = String::from("/examples:stats/counter2");
             let val2 = LydValue::from_string("1052".to_string());
 
-            let parent = LibYang::lyd_new_path(None, Some(ctx), &path1, Some(&val1), 0).unwrap();
-            LibYang::lyd_new_path(Some(&parent), None, &path2, Some(&val2), 0);
-
-            Some(parent)
+            let node = match parent {
+                Some(parent) => {
+                    parent.new_path(&path1, Some(&val1), 0)?;
+                    parent.new_path(&path2, Some(&val2), 0)?;
+                    None
+                }
+                None => {
+                    let mut tree = LibYang::lyd_new_path(ctx, &path1, Some(&val1), 0)?;
+                    tree.new_path(&path2, Some(&val2), 0)?;
+                    Some(tree)
+                }
+            };
+
+            Ok(node)
         } else {
-            None
+            Ok(None)
         }
     };
 
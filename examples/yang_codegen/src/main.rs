@@ -0,0 +1,79 @@
+//
+// Sysrepo-examples.
+//   yang_codegen
+//
+
+use std::env;
+
+use sysrepo::codegen::CodeGen;
+
+/// Show help.
+fn print_help(program: &str) {
+    println!(
+        "Usage: {} <search-dir> <module-name-or-file>... [-o <output-file>]",
+        program
+    );
+}
+
+/// Main.
+fn main() {
+    if run() {
+        std::process::exit(0);
+    } else {
+        std::process::exit(1);
+    }
+}
+
+fn run() -> bool {
+    let mut args: Vec<String> = env::args().collect();
+    let program = args[0].clone();
+
+    // Output file, or standard output.
+    let mut output = None;
+    if let Some(pos) = args.iter().position(|arg| arg == "-o") {
+        if pos + 1 >= args.len() {
+            print_help(&program);
+            return false;
+        }
+        output = Some(args.remove(pos + 1));
+        args.remove(pos);
+    }
+
+    if args.len() < 3 {
+        print_help(&program);
+        return false;
+    }
+
+    let mut codegen = match CodeGen::new(&[&args[1]]) {
+        Ok(codegen) => codegen,
+        Err(err) => {
+            eprintln!("Failed to create context: {}", err);
+            return false;
+        }
+    };
+
+    // Files are parsed, other arguments are loaded from search directory.
+    for arg in &args[2..] {
+        let res = if arg.ends_with(".yang") || arg.ends_with(".yin") {
+            codegen.parse_module_path(arg)
+        } else {
+            codegen.load_module(arg, None, &["*"])
+        };
+        if let Err(err) = res {
+            eprintln!("Failed to load module \"{}\": {}", arg, err);
+            return false;
+        }
+    }
+
+    match output {
+        Some(path) => {
+            if let Err(err) = codegen.write_to_file(&path) {
+                eprintln!("Failed to write \"{}\": {}", path, err);
+                return false;
+            }
+        }
+        None => print!("{}", codegen.generate()),
+    }
+
+    true
+}
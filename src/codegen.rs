@@ -0,0 +1,1047 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::lysc_type;
+use crate::xpath_quote;
+use crate::LibYang;
+use crate::LibYangCtx;
+use crate::LyDataType;
+use crate::LyError;
+use crate::LydNode;
+use crate::LydNodeRef;
+use crate::LysInFormat;
+use crate::LysModule;
+use crate::LysNodeType;
+use crate::LyscNode;
+use crate::LyscType;
+use crate::LY_ERR_LY_EINVAL;
+
+/// Rust keywords, which have to be escaped in field names.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+/// Type names used by generated module itself.
+const RESERVED_TYPES: &[&str] = &[
+    "Data",
+    "Identity",
+    "FromStr",
+    "LibYangCtx",
+    "LyError",
+    "LydNode",
+    "LydNodeRef",
+    "Option",
+    "Result",
+    "Self",
+    "Siblings",
+    "String",
+    "Vec",
+];
+
+/// Method names used by generated structs themselves.
+const RESERVED_METHODS: &[&str] = &[
+    "xpath",
+    "instance_xpath",
+    "from_tree",
+    "to_tree",
+    "from_node",
+    "from_siblings",
+    "to_siblings",
+];
+
+/// Parse value of leaf or leaf-list node, used by generated code.
+pub fn parse_value<T: FromStr>(node: LydNodeRef) -> Result<T, LyError> {
+    let value = node.value().unwrap_or_default();
+
+    value.parse::<T>().map_err(|_| {
+        LyError::from_message(
+            LY_ERR_LY_EINVAL,
+            format!("Invalid value \"{}\" of \"{}\"", value, node.schema_name()),
+            Some(node.path()),
+        )
+    })
+}
+
+/// Quote value for XPath predicate, used by generated code.
+///  Value containing both single and double quotes is reported as error.
+pub fn quote(value: &str) -> Result<String, LyError> {
+    xpath_quote(value)
+}
+
+/// Place to create data nodes in, used by generated code.
+pub enum Siblings<'a> {
+    /// Children of existing node.
+    Parent(LydNodeRef<'a>),
+
+    /// Top-level siblings of tree, none until first node is created in context.
+    Tree(&'a LibYangCtx, &'a mut Option<LydNode>),
+}
+
+impl<'a> Siblings<'a> {
+    /// Create leaf or leaf-list node.
+    pub fn new_term(&mut self, mod_name: &str, name: &str, value: &str) -> Result<(), LyError> {
+        match self {
+            Siblings::Parent(parent) => parent.new_term(Some(mod_name), name, value, false)?,
+            Siblings::Tree(ctx, tree) => Siblings::attach(
+                tree,
+                LibYang::lyd_new_term(ctx, mod_name, name, value, false)?,
+            )?,
+        };
+
+        Ok(())
+    }
+
+    /// Create container node.
+    pub fn new_inner(&mut self, mod_name: &str, name: &str) -> Result<LydNodeRef<'_>, LyError> {
+        match self {
+            Siblings::Parent(parent) => parent.new_inner(Some(mod_name), name, false),
+            Siblings::Tree(ctx, tree) => {
+                Siblings::attach(tree, LibYang::lyd_new_inner(ctx, mod_name, name, false)?)
+            }
+        }
+    }
+
+    /// Create list instance with given keys.
+    pub fn new_list(
+        &mut self,
+        mod_name: &str,
+        name: &str,
+        keys: &[(&str, &str)],
+    ) -> Result<LydNodeRef<'_>, LyError> {
+        match self {
+            Siblings::Parent(parent) => parent.new_list(Some(mod_name), name, keys, false),
+            Siblings::Tree(ctx, tree) => Siblings::attach(
+                tree,
+                LibYang::lyd_new_list(ctx, mod_name, name, keys, false)?,
+            ),
+        }
+    }
+
+    /// Attach node to top-level siblings in tree, and return it.
+    fn attach(tree: &mut Option<LydNode>, node: LydNode) -> Result<LydNodeRef<'_>, LyError> {
+        let raw = node.get_node();
+        let joined = match tree.take() {
+            Some(first) => LibYang::lyd_insert_sibling(first, node)?,
+            None => node,
+        };
+
+        Ok(tree.insert(joined).node_ref(raw))
+    }
+}
+
+/// YANG to Rust code generator.
+///  Modules are loaded into own context, and Rust module is generated for each of them,
+///  to be written from build script or standalone tool.
+pub struct CodeGen {
+    /// Context modules are loaded into.
+    ctx: LibYangCtx,
+
+    /// Names of modules to generate code for.
+    modules: Vec<String>,
+}
+
+impl CodeGen {
+    /// Create generator, modules and their imports are searched in given directories.
+    pub fn new(search_dirs: &[&str]) -> Result<CodeGen, LyError> {
+        Ok(CodeGen {
+            ctx: LibYangCtx::new(search_dirs, 0)?,
+            modules: Vec::new(),
+        })
+    }
+
+    /// Return context modules are loaded into.
+    pub fn context(&self) -> &LibYangCtx {
+        &self.ctx
+    }
+
+    /// Load module from search directories, and generate code for it.
+    pub fn load_module(
+        &mut self,
+        name: &str,
+        revision: Option<&str>,
+        features: &[&str],
+    ) -> Result<(), LyError> {
+        let name = self
+            .ctx
+            .load_module(name, revision, features)?
+            .name()
+            .to_string();
+        self.add_module(name);
+        Ok(())
+    }
+
+    /// Parse module from file, as YIN if it has ".yin" extension, and generate code for it.
+    pub fn parse_module_path(&mut self, path: &str) -> Result<(), LyError> {
+        let format = if path.ends_with(".yin") {
+            LysInFormat::Yin
+        } else {
+            LysInFormat::Yang
+        };
+        let name = self.ctx.parse_module_path(path, format)?.name().to_string();
+        self.add_module(name);
+        Ok(())
+    }
+
+    fn add_module(&mut self, name: String) {
+        if !self.modules.contains(&name) {
+            self.modules.push(name);
+        }
+    }
+
+    /// Return generated code of all modules.
+    pub fn generate(&self) -> String {
+        let mut out = String::from("// Generated by sysrepo codegen, do not edit.\n");
+
+        for name in &self.modules {
+            if let Some(module) = self.ctx.get_module_implemented(name) {
+                out.push('\n');
+                out.push_str(&ModuleGen::new(&module).generate());
+            }
+        }
+
+        out
+    }
+
+    /// Write generated code to file, e.g. in OUT_DIR to be included by the crate.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.generate())
+    }
+}
+
+/// Convert YANG identifier to CamelCase type name.
+fn camel_case(name: &str) -> String {
+    let mut s = String::with_capacity(name.len());
+    let mut upper = true;
+
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            s.push(if upper { c.to_ascii_uppercase() } else { c });
+            upper = false;
+        } else {
+            upper = true;
+        }
+    }
+
+    if s.is_empty() || s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.insert(0, 'V');
+    }
+    s
+}
+
+/// Convert YANG identifier to snake_case name, without escaping keywords.
+fn snake_case(name: &str) -> String {
+    let mut s = String::with_capacity(name.len());
+    let mut lower = false;
+
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if lower {
+                s.push('_');
+            }
+            s.push(c.to_ascii_lowercase());
+            lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            s.push(c);
+            lower = true;
+        } else {
+            s.push('_');
+            lower = false;
+        }
+    }
+
+    if s.is_empty() || s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.insert(0, '_');
+    }
+    s
+}
+
+/// Convert YANG identifier to field or module name, escaping keywords.
+fn field_name(name: &str) -> String {
+    let s = snake_case(name);
+
+    match s.as_str() {
+        "self" | "super" | "crate" => format!("{}_", s),
+        _ if KEYWORDS.contains(&s.as_str()) => format!("r#{}", s),
+        _ => s,
+    }
+}
+
+/// Make name unique among used ones by appending number.
+fn unique(used: &mut HashSet<String>, name: String) -> String {
+    let mut unique = name.clone();
+    let mut n = 2;
+
+    while used.contains(&unique) {
+        unique = format!("{}{}", name, n);
+        n += 1;
+    }
+    used.insert(unique.clone());
+
+    unique
+}
+
+/// Kind of generated struct.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum StructKind {
+    Root,
+    Container,
+    List,
+    Case,
+}
+
+/// Kind of generated struct field, with its Rust type.
+enum FieldKind {
+    Leaf(String),
+    Key(String),
+    Empty,
+    LeafList(String),
+    Container(String),
+    List(String, Vec<(String, String)>),
+    Choice(String),
+}
+
+/// Generated struct field.
+struct Field {
+    /// Field name.
+    name: String,
+
+    /// Schema node module name.
+    module: String,
+
+    /// Schema node name.
+    yang_name: String,
+
+    /// Field kind.
+    kind: FieldKind,
+}
+
+impl Field {
+    /// Return module qualified schema node name.
+    fn qualified_name(&self) -> String {
+        format!("{}:{}", self.module, self.yang_name)
+    }
+
+    /// Return field name without raw identifier prefix.
+    fn base_name(&self) -> &str {
+        self.name.trim_start_matches("r#").trim_end_matches('_')
+    }
+}
+
+/// Code generator of single module.
+struct ModuleGen<'a> {
+    /// Module to generate code for.
+    module: &'a LysModule<'a>,
+
+    /// Generated type names by compiled type.
+    types: HashMap<*const lysc_type, String>,
+
+    /// Used type names.
+    names: HashSet<String>,
+
+    /// Generated code.
+    out: String,
+}
+
+impl<'a> ModuleGen<'a> {
+    fn new(module: &'a LysModule<'a>) -> Self {
+        Self {
+            module: module,
+            types: HashMap::new(),
+            names: RESERVED_TYPES.iter().map(|name| name.to_string()).collect(),
+            out: String::new(),
+        }
+    }
+
+    /// Append line with indentation.
+    fn line(&mut self, indent: usize, text: &str) {
+        if !text.is_empty() {
+            for _ in 0..indent {
+                self.out.push_str("    ");
+            }
+            self.out.push_str(text);
+        }
+        self.out.push('\n');
+    }
+
+    /// Return unique type name.
+    fn type_name(&mut self, name: &str) -> String {
+        unique(&mut self.names, name.to_string())
+    }
+
+    fn generate(mut self) -> String {
+        let module = self.module;
+        let name = module.name().to_string();
+
+        match module.revision() {
+            Some(revision) => self.line(
+                0,
+                &format!("/// Generated from YANG module \"{}@{}\".", name, revision),
+            ),
+            None => self.line(0, &format!("/// Generated from YANG module \"{}\".", name)),
+        }
+        self.line(0, &format!("pub mod {} {{", field_name(&name)));
+        self.line(
+            1,
+            "#![allow(dead_code, unused_imports, unused_mut, unused_variables)]",
+        );
+        self.line(0, "");
+        self.line(1, "use std::fmt;");
+        self.line(1, "use std::str::FromStr;");
+        self.line(0, "");
+        self.line(1, "use sysrepo::codegen::{parse_value, quote, Siblings};");
+        self.line(
+            1,
+            "use sysrepo::{LibYangCtx, LyError, LydNode, LydNodeRef};",
+        );
+        self.line(0, "");
+        self.line(1, "/// Module name.");
+        self.line(1, &format!("pub const MODULE_NAME: &str = {:?};", name));
+        self.line(0, "");
+
+        self.gen_typedefs();
+        self.gen_identities();
+        self.gen_struct("Data", None, StructKind::Root);
+
+        // Drop blank line after last item.
+        self.out.pop();
+        self.line(0, "}");
+
+        self.out
+    }
+
+    /// Generate type aliases and enums for typedefs used in the module.
+    fn gen_typedefs(&mut self) {
+        for (name, ty) in self.module.typedefs() {
+            let ty = match ty {
+                Some(ty) => ty,
+                None => continue,
+            };
+            if ty.base_type() == LyDataType::Empty {
+                continue;
+            }
+
+            let type_name = self.type_name(&camel_case(&name));
+            let doc = format!("Typedef \"{}\".", name);
+            if ty.base_type() == LyDataType::Enum {
+                let items: Vec<String> = ty.enums().into_iter().map(|(name, _)| name).collect();
+                self.gen_enum(&type_name, &doc, &items, None);
+            } else {
+                let rust_type = self.rust_type(&ty, &type_name);
+                self.line(1, &format!("/// {}", doc));
+                self.line(1, &format!("pub type {} = {};", type_name, rust_type));
+                self.line(0, "");
+            }
+            self.types.insert(ty.get_type(), type_name);
+        }
+    }
+
+    /// Generate enum of identities defined in the module.
+    fn gen_identities(&mut self) {
+        let items: Vec<String> = self
+            .module
+            .identities()
+            .iter()
+            .map(|ident| ident.name().to_string())
+            .collect();
+
+        if !items.is_empty() {
+            let module = self.module.name().to_string();
+            self.gen_enum(
+                "Identity",
+                "Identities defined in the module.",
+                &items,
+                Some(&module),
+            );
+        }
+    }
+
+    /// Return Rust type for compiled type, generating enum with given name if needed.
+    fn rust_type(&mut self, ty: &LyscType, enum_name: &str) -> String {
+        if let Some(name) = self.types.get(&ty.get_type()) {
+            return name.clone();
+        }
+
+        match ty.base_type() {
+            LyDataType::Int8 => "i8".to_string(),
+            LyDataType::Int16 => "i16".to_string(),
+            LyDataType::Int32 => "i32".to_string(),
+            LyDataType::Int64 => "i64".to_string(),
+            LyDataType::UInt8 => "u8".to_string(),
+            LyDataType::UInt16 => "u16".to_string(),
+            LyDataType::UInt32 => "u32".to_string(),
+            LyDataType::UInt64 => "u64".to_string(),
+            LyDataType::Bool => "bool".to_string(),
+            // Canonical value, since floating point can not keep exact fraction digits.
+            LyDataType::Decimal64 => "String".to_string(),
+            LyDataType::Enum => {
+                let name = self.type_name(enum_name);
+                let items: Vec<String> = ty.enums().into_iter().map(|(name, _)| name).collect();
+                self.gen_enum(&name, "Enumeration.", &items, None);
+                self.types.insert(ty.get_type(), name.clone());
+                name
+            }
+            LyDataType::LeafRef => match ty.leafref_real_type() {
+                Some(real) => self.rust_type(&real, enum_name),
+                None => "String".to_string(),
+            },
+            _ => "String".to_string(),
+        }
+    }
+
+    /// Generate enum with string conversions, identity names are qualified with module.
+    fn gen_enum(&mut self, name: &str, doc: &str, items: &[String], module: Option<&str>) {
+        let mut used = HashSet::new();
+        let variants: Vec<String> = items
+            .iter()
+            .map(|item| unique(&mut used, camel_case(item)))
+            .collect();
+
+        self.line(1, &format!("/// {}", doc));
+        self.line(1, "#[derive(Clone, Copy, Debug, Eq, PartialEq)]");
+        self.line(1, &format!("pub enum {} {{", name));
+        for (item, variant) in items.iter().zip(&variants) {
+            self.line(2, &format!("/// {:?}.", item));
+            self.line(2, &format!("{},", variant));
+        }
+        self.line(1, "}");
+        self.line(0, "");
+
+        self.line(1, &format!("impl Default for {} {{", name));
+        self.line(2, "fn default() -> Self {");
+        self.line(3, &format!("{}::{}", name, variants[0]));
+        self.line(2, "}");
+        self.line(1, "}");
+        self.line(0, "");
+
+        self.line(1, &format!("impl FromStr for {} {{", name));
+        self.line(2, "type Err = String;");
+        self.line(0, "");
+        self.line(2, "fn from_str(s: &str) -> Result<Self, String> {");
+        self.line(3, "match s {");
+        for (item, variant) in items.iter().zip(&variants) {
+            match module {
+                Some(module) => self.line(
+                    4,
+                    &format!(
+                        "{:?} | {:?} => Ok({}::{}),",
+                        item,
+                        format!("{}:{}", module, item),
+                        name,
+                        variant
+                    ),
+                ),
+                None => self.line(4, &format!("{:?} => Ok({}::{}),", item, name, variant)),
+            }
+        }
+        self.line(4, "_ => Err(format!(\"Unknown value \\\"{}\\\"\", s)),");
+        self.line(3, "}");
+        self.line(2, "}");
+        self.line(1, "}");
+        self.line(0, "");
+
+        self.line(1, &format!("impl fmt::Display for {} {{", name));
+        self.line(2, "fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {");
+        self.line(3, "let s = match self {");
+        for (item, variant) in items.iter().zip(&variants) {
+            let value = match module {
+                Some(module) => format!("{}:{}", module, item),
+                None => item.clone(),
+            };
+            self.line(4, &format!("{}::{} => {:?},", name, variant, value));
+        }
+        self.line(3, "};");
+        self.line(3, "write!(f, \"{}\", s)");
+        self.line(2, "}");
+        self.line(1, "}");
+        self.line(0, "");
+    }
+
+    /// Generate struct for children of node, or for top-level nodes of the module.
+    ///  Return names and field names of list keys.
+    fn gen_struct(
+        &mut self,
+        name: &str,
+        node: Option<&LyscNode>,
+        kind: StructKind,
+    ) -> Vec<(String, String)> {
+        let children: Vec<LyscNode> = match node {
+            Some(node) => node.children().collect(),
+            None => self.module.data_nodes().collect(),
+        };
+        let prefix = if kind == StructKind::Root { "" } else { name };
+        let mut used = HashSet::new();
+        let mut fields = Vec::new();
+
+        for child in children {
+            let type_base = format!("{}{}", prefix, camel_case(child.name()));
+            let field_kind = match child.kind() {
+                LysNodeType::Leaf | LysNodeType::LeafList => {
+                    let ty = match child.leaf_type() {
+                        Some(ty) => ty,
+                        None => continue,
+                    };
+                    // Keys and leaf-lists of type empty hold empty strings.
+                    match (child.kind(), ty.base_type()) {
+                        (LysNodeType::Leaf, _) if child.is_key() => {
+                            FieldKind::Key(self.rust_type(&ty, &type_base))
+                        }
+                        (LysNodeType::Leaf, LyDataType::Empty) => FieldKind::Empty,
+                        (LysNodeType::Leaf, _) => FieldKind::Leaf(self.rust_type(&ty, &type_base)),
+                        _ => FieldKind::LeafList(self.rust_type(&ty, &type_base)),
+                    }
+                }
+                LysNodeType::Container => {
+                    let type_name = self.type_name(&type_base);
+                    self.gen_struct(&type_name, Some(&child), StructKind::Container);
+                    FieldKind::Container(type_name)
+                }
+                LysNodeType::List => {
+                    let type_name = self.type_name(&type_base);
+                    let keys = self.gen_struct(&type_name, Some(&child), StructKind::List);
+                    FieldKind::List(type_name, keys)
+                }
+                LysNodeType::Choice if child.children().next().is_some() => {
+                    let type_name = self.type_name(&type_base);
+                    self.gen_choice(&type_name, &child);
+                    FieldKind::Choice(type_name)
+                }
+                _ => continue,
+            };
+
+            fields.push(Field {
+                name: unique(&mut used, field_name(child.name())),
+                module: child.module().name().to_string(),
+                yang_name: child.name().to_string(),
+                kind: field_kind,
+            });
+        }
+
+        let doc = match (kind, node) {
+            (StructKind::Container, Some(node)) => format!("Container {:?}.", node.name()),
+            (StructKind::List, Some(node)) => format!("List {:?}.", node.name()),
+            (StructKind::Case, Some(node)) => format!("Case {:?}.", node.name()),
+            _ => "Top-level data nodes of the module.".to_string(),
+        };
+        self.line(1, &format!("/// {}", doc));
+        self.line(1, "#[derive(Clone, Debug, Default, PartialEq)]");
+        self.line(1, &format!("pub struct {} {{", name));
+        for field in &fields {
+            let (doc, ty) = match &field.kind {
+                FieldKind::Leaf(ty) => ("Leaf", format!("Option<{}>", ty)),
+                FieldKind::Key(ty) => ("Key leaf", ty.clone()),
+                FieldKind::Empty => ("Leaf", "bool".to_string()),
+                FieldKind::LeafList(ty) => ("Leaf-list", format!("Vec<{}>", ty)),
+                FieldKind::Container(ty) => ("Container", format!("Option<{}>", ty)),
+                FieldKind::List(ty, _) => ("List", format!("Vec<{}>", ty)),
+                FieldKind::Choice(ty) => ("Choice", format!("Option<{}>", ty)),
+            };
+            self.line(2, &format!("/// {} {:?}.", doc, field.yang_name));
+            self.line(2, &format!("pub {}: {},", field.name, ty));
+        }
+        self.line(1, "}");
+        self.line(0, "");
+
+        self.line(1, &format!("impl {} {{", name));
+        if let Some(node) = node {
+            if kind != StructKind::Case {
+                self.gen_xpath(node, kind, &fields);
+            }
+        }
+        self.gen_leaf_xpaths(&fields);
+        self.gen_entry_points(kind);
+        self.gen_from_siblings(&fields);
+        self.gen_to_siblings(&fields);
+        self.line(1, "}");
+        self.line(0, "");
+
+        fields
+            .iter()
+            .filter(|field| matches!(field.kind, FieldKind::Key(_)))
+            .map(|field| (field.yang_name.clone(), field.name.clone()))
+            .collect()
+    }
+
+    /// Generate enum with variant for each case of choice.
+    fn gen_choice(&mut self, name: &str, choice: &LyscNode) {
+        let mut used = HashSet::new();
+        let mut cases = Vec::new();
+
+        for case in choice.children() {
+            let type_name = self.type_name(&format!("{}{}", name, camel_case(case.name())));
+            self.gen_struct(&type_name, Some(&case), StructKind::Case);
+            cases.push((
+                case.name().to_string(),
+                unique(&mut used, camel_case(case.name())),
+                type_name,
+            ));
+        }
+
+        self.line(1, &format!("/// Choice {:?}.", choice.name()));
+        self.line(1, "#[derive(Clone, Debug, PartialEq)]");
+        self.line(1, &format!("pub enum {} {{", name));
+        for (case, variant, type_name) in &cases {
+            self.line(2, &format!("/// Case {:?}.", case));
+            self.line(2, &format!("{}({}),", variant, type_name));
+        }
+        self.line(1, "}");
+        self.line(0, "");
+
+        self.line(1, &format!("impl {} {{", name));
+        self.line(
+            2,
+            "/// Read case present among data nodes, starting at first sibling.",
+        );
+        self.line(
+            2,
+            "pub fn from_siblings(first: Option<LydNodeRef>) -> Result<Option<Self>, LyError> {",
+        );
+        for (_, variant, type_name) in &cases {
+            self.line(
+                3,
+                &format!("let case = {}::from_siblings(first)?;", type_name),
+            );
+            self.line(3, &format!("if case != {}::default() {{", type_name));
+            self.line(4, &format!("return Ok(Some({}::{}(case)));", name, variant));
+            self.line(3, "}");
+        }
+        self.line(3, "Ok(None)");
+        self.line(2, "}");
+        self.line(0, "");
+        self.line(
+            2,
+            "/// Create data nodes of the case under parent, or as top-level siblings in tree.",
+        );
+        self.line(
+            2,
+            "pub fn to_siblings(&self, siblings: &mut Siblings) -> Result<(), LyError> {",
+        );
+        self.line(3, "match self {");
+        for (_, variant, _) in &cases {
+            self.line(
+                4,
+                &format!("{}::{}(case) => case.to_siblings(siblings),", name, variant),
+            );
+        }
+        self.line(3, "}");
+        self.line(2, "}");
+        self.line(1, "}");
+        self.line(0, "");
+    }
+
+    /// Generate schema path and data path accessors of container or list.
+    fn gen_xpath(&mut self, node: &LyscNode, kind: StructKind, fields: &[Field]) {
+        let qualified = format!("{}:{}", node.module().name(), node.name());
+
+        self.line(2, "/// Schema path.");
+        self.line(
+            2,
+            &format!("pub const SCHEMA_PATH: &'static str = {:?};", node.path()),
+        );
+        self.line(0, "");
+
+        if kind == StructKind::Container {
+            self.line(
+                2,
+                "/// Return data path under parent data path, empty for top-level.",
+            );
+            self.line(2, "pub fn xpath(parent_path: &str) -> String {");
+            self.line(3, &format!("format!(\"{{}}/{}\", parent_path)", qualified));
+            self.line(2, "}");
+            self.line(0, "");
+            return;
+        }
+
+        let keys: Vec<&Field> = fields
+            .iter()
+            .filter(|field| matches!(field.kind, FieldKind::Key(_)))
+            .collect();
+        let params: String = keys
+            .iter()
+            .map(|key| format!(", {}: &str", key.name))
+            .collect();
+        let predicates: String = keys
+            .iter()
+            .map(|key| format!("[{}={{}}]", key.yang_name))
+            .collect();
+        let args: String = keys
+            .iter()
+            .map(|key| format!(", quote({})?", key.name))
+            .collect();
+        let values: String = keys
+            .iter()
+            .map(|key| format!(", &self.{}.to_string()", key.name))
+            .collect();
+
+        self.line(
+            2,
+            "/// Return data path of list instance under parent data path, empty for top-level.",
+        );
+        self.line(
+            2,
+            &format!(
+                "pub fn xpath(parent_path: &str{}) -> Result<String, LyError> {{",
+                params
+            ),
+        );
+        self.line(
+            3,
+            &format!(
+                "Ok(format!(\"{{}}/{}{}\", parent_path{}))",
+                qualified, predicates, args
+            ),
+        );
+        self.line(2, "}");
+        self.line(0, "");
+
+        self.line(
+            2,
+            "/// Return data path of this list instance under parent data path.",
+        );
+        self.line(
+            2,
+            "pub fn instance_xpath(&self, parent_path: &str) -> Result<String, LyError> {",
+        );
+        self.line(3, &format!("Self::xpath(parent_path{})", values));
+        self.line(2, "}");
+        self.line(0, "");
+    }
+
+    /// Generate data path accessors of leaves and leaf-lists.
+    fn gen_leaf_xpaths(&mut self, fields: &[Field]) {
+        let mut used: HashSet<String> = RESERVED_METHODS
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        for field in fields {
+            let doc = match field.kind {
+                FieldKind::Leaf(_) | FieldKind::Key(_) | FieldKind::Empty => "leaf",
+                FieldKind::LeafList(_) => "leaf-list",
+                _ => continue,
+            };
+
+            self.line(
+                2,
+                &format!(
+                    "/// Return data path of {} {:?} under data path of its parent.",
+                    doc, field.yang_name
+                ),
+            );
+            let method = unique(&mut used, format!("{}_xpath", field.base_name()));
+            self.line(2, &format!("pub fn {}(path: &str) -> String {{", method));
+            self.line(
+                3,
+                &format!("format!(\"{{}}/{}\", path)", field.qualified_name()),
+            );
+            self.line(2, "}");
+            self.line(0, "");
+        }
+    }
+
+    /// Generate conversions from and to data node or tree.
+    fn gen_entry_points(&mut self, kind: StructKind) {
+        match kind {
+            StructKind::Root => {
+                self.line(2, "/// Read from data tree.");
+                self.line(
+                    2,
+                    "pub fn from_tree(tree: &LydNode) -> Result<Self, LyError> {",
+                );
+                self.line(
+                    3,
+                    "Self::from_siblings(tree.as_ref().map(|root| root.first_sibling()))",
+                );
+                self.line(2, "}");
+                self.line(0, "");
+                self.line(2, "/// Create data tree, none if there is no data.");
+                self.line(
+                    2,
+                    "pub fn to_tree(&self, ctx: &LibYangCtx) -> Result<Option<LydNode>, LyError> {",
+                );
+                self.line(3, "let mut tree = None;");
+                self.line(3, "self.to_siblings(&mut Siblings::Tree(ctx, &mut tree))?;");
+                self.line(3, "Ok(tree)");
+                self.line(2, "}");
+                self.line(0, "");
+            }
+            StructKind::Container | StructKind::List => {
+                self.line(2, "/// Read from data node.");
+                self.line(
+                    2,
+                    "pub fn from_node(node: LydNodeRef) -> Result<Self, LyError> {",
+                );
+                self.line(3, "Self::from_siblings(node.first_child())");
+                self.line(2, "}");
+                self.line(0, "");
+            }
+            StructKind::Case => {}
+        }
+    }
+
+    /// Generate reading of fields from data nodes.
+    fn gen_from_siblings(&mut self, fields: &[Field]) {
+        self.line(
+            2,
+            "/// Read fields from data nodes, starting at first sibling.",
+        );
+        self.line(
+            2,
+            "pub fn from_siblings(first: Option<LydNodeRef>) -> Result<Self, LyError> {",
+        );
+        self.line(3, "let mut data = Self::default();");
+        self.line(3, "let mut next = first;");
+        self.line(3, "while let Some(node) = next {");
+        self.line(
+            4,
+            "match (node.module_name().unwrap_or(\"\"), node.schema_name()) {",
+        );
+        for field in fields {
+            let read = match &field.kind {
+                FieldKind::Leaf(_) => format!("data.{} = Some(parse_value(node)?)", field.name),
+                FieldKind::Key(_) => format!("data.{} = parse_value(node)?", field.name),
+                FieldKind::Empty => format!("data.{} = true", field.name),
+                FieldKind::LeafList(_) => format!("data.{}.push(parse_value(node)?)", field.name),
+                FieldKind::Container(ty) => {
+                    format!("data.{} = Some({}::from_node(node)?)", field.name, ty)
+                }
+                FieldKind::List(ty, _) => {
+                    format!("data.{}.push({}::from_node(node)?)", field.name, ty)
+                }
+                FieldKind::Choice(_) => continue,
+            };
+            self.line(
+                5,
+                &format!("({:?}, {:?}) => {},", field.module, field.yang_name, read),
+            );
+        }
+        self.line(5, "_ => {}");
+        self.line(4, "}");
+        self.line(4, "next = node.next_sibling();");
+        self.line(3, "}");
+        for field in fields {
+            if let FieldKind::Choice(ty) = &field.kind {
+                self.line(
+                    3,
+                    &format!("data.{} = {}::from_siblings(first)?;", field.name, ty),
+                );
+            }
+        }
+        self.line(3, "Ok(data)");
+        self.line(2, "}");
+        self.line(0, "");
+    }
+
+    /// Generate creation of data nodes from fields, list keys are created with the list.
+    fn gen_to_siblings(&mut self, fields: &[Field]) {
+        self.line(
+            2,
+            "/// Create data nodes of fields under parent, or as top-level siblings in tree.",
+        );
+        self.line(
+            2,
+            "pub fn to_siblings(&self, siblings: &mut Siblings) -> Result<(), LyError> {",
+        );
+        for field in fields {
+            let args = format!("{:?}, {:?}", field.module, field.yang_name);
+            match &field.kind {
+                FieldKind::Leaf(_) => {
+                    self.line(3, &format!("if let Some(value) = &self.{} {{", field.name));
+                    self.line(
+                        4,
+                        &format!("siblings.new_term({}, &value.to_string())?;", args),
+                    );
+                    self.line(3, "}");
+                }
+                FieldKind::Key(_) => {}
+                FieldKind::Empty => {
+                    self.line(3, &format!("if self.{} {{", field.name));
+                    self.line(4, &format!("siblings.new_term({}, \"\")?;", args));
+                    self.line(3, "}");
+                }
+                FieldKind::LeafList(_) => {
+                    self.line(3, &format!("for value in &self.{} {{", field.name));
+                    self.line(
+                        4,
+                        &format!("siblings.new_term({}, &value.to_string())?;", args),
+                    );
+                    self.line(3, "}");
+                }
+                FieldKind::Container(_) => {
+                    self.line(3, &format!("if let Some(child) = &self.{} {{", field.name));
+                    self.line(4, &format!("let node = siblings.new_inner({})?;", args));
+                    self.line(4, "child.to_siblings(&mut Siblings::Parent(node))?;");
+                    self.line(3, "}");
+                }
+                FieldKind::List(_, keys) => {
+                    let keys = keys
+                        .iter()
+                        .map(|(key, name)| {
+                            format!("({:?}, child.{}.to_string().as_str())", key, name)
+                        })
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    self.line(3, &format!("for child in &self.{} {{", field.name));
+                    self.line(
+                        4,
+                        &format!("let node = siblings.new_list({}, &[{}])?;", args, keys),
+                    );
+                    self.line(4, "child.to_siblings(&mut Siblings::Parent(node))?;");
+                    self.line(3, "}");
+                }
+                FieldKind::Choice(_) => {
+                    self.line(3, &format!("if let Some(child) = &self.{} {{", field.name));
+                    self.line(4, "child.to_siblings(siblings)?;");
+                    self.line(3, "}");
+                }
+            }
+        }
+        self.line(3, "Ok(())");
+        self.line(2, "}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn camel_case_names() {
+        assert_eq!(camel_case("ietf-interfaces"), "IetfInterfaces");
+        assert_eq!(camel_case("oper_status"), "OperStatus");
+        assert_eq!(camel_case("2nd-hop"), "V2ndHop");
+        assert_eq!(camel_case(""), "V");
+    }
+
+    #[test]
+    fn snake_case_names() {
+        assert_eq!(snake_case("oper-status"), "oper_status");
+        assert_eq!(snake_case("ifIndex"), "if_index");
+        assert_eq!(snake_case("IPv4"), "ipv4");
+        assert_eq!(snake_case("2nd"), "_2nd");
+    }
+
+    #[test]
+    fn field_names_escape_keywords() {
+        assert_eq!(field_name("type"), "r#type");
+        assert_eq!(field_name("self"), "self_");
+        assert_eq!(field_name("crate"), "crate_");
+        assert_eq!(field_name("mtu"), "mtu");
+    }
+
+    #[test]
+    fn unique_names() {
+        let mut used = HashSet::new();
+        assert_eq!(unique(&mut used, "name".to_string()), "name");
+        assert_eq!(unique(&mut used, "name".to_string()), "name2");
+        assert_eq!(unique(&mut used, "name".to_string()), "name3");
+        assert_eq!(unique(&mut used, "name2".to_string()), "name22");
+    }
+}
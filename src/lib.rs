@@ -4,17 +4,26 @@
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
+pub mod codegen;
+#[cfg(feature = "serde")]
+pub mod lyd_serde;
+
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::fmt;
+use std::io::Write;
+use std::marker::PhantomData;
 use std::mem;
 use std::mem::zeroed;
 use std::os::raw::c_char;
 use std::os::raw::c_void;
+use std::rc::Rc;
 use std::slice;
 use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 use libc;
 
@@ -39,6 +48,110 @@ pub enum SrError {
     CallbackShelve = sr_error_t_SR_ERR_CALLBACK_SHELVE as isize,
 }
 
+/// LibYang error item.
+#[derive(Clone, Debug)]
+pub struct LyErrorItem {
+    /// LY_ERR code.
+    pub code: i32,
+
+    /// Validation error code.
+    pub vecode: u32,
+
+    /// Error message.
+    pub message: String,
+
+    /// Location of the error, schema or data path and line number.
+    pub path: Option<String>,
+}
+
+/// LibYang error.
+#[derive(Clone, Debug)]
+pub struct LyError {
+    /// LY_ERR code returned by the failed call.
+    pub code: i32,
+
+    /// Errors recorded in the context, may be empty.
+    pub items: Vec<LyErrorItem>,
+}
+
+impl LyError {
+    /// Constructor for error detected on the Rust side.
+    fn from_message(code: LY_ERR, message: String, path: Option<String>) -> Self {
+        Self {
+            code: code as i32,
+            items: vec![LyErrorItem {
+                code: code as i32,
+                vecode: 0,
+                message: message,
+                path: path,
+            }],
+        }
+    }
+}
+
+impl fmt::Display for LyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.items.last() {
+            Some(item) => match &item.path {
+                Some(path) => write!(f, "{} ({})", item.message, path),
+                None => write!(f, "{}", item.message),
+            },
+            None => write!(f, "LibYang error {}", self.code),
+        }
+    }
+}
+
+/// Error of subscription callback, message is reported to the originator.
+#[derive(Clone, Debug)]
+pub struct SrCallbackError {
+    /// Sysrepo error code, success is reported as callback failure.
+    pub code: i32,
+
+    /// Error message.
+    pub message: Option<String>,
+}
+
+impl SrCallbackError {
+    /// Constructor with error message.
+    pub fn new(code: i32, message: &str) -> Self {
+        Self {
+            code: code,
+            message: Some(message.to_string()),
+        }
+    }
+
+    /// Set error message on the session and return error code for sysrepo.
+    fn report(self, sess: &mut SrSession) -> i32 {
+        if let Some(message) = &self.message {
+            let _ = sess.set_error_message(message);
+        }
+
+        if self.code == SrError::Ok as i32 {
+            SrError::CallbackFailed as i32
+        } else {
+            self.code
+        }
+    }
+}
+
+impl From<i32> for SrCallbackError {
+    fn from(code: i32) -> Self {
+        Self {
+            code: code,
+            message: None,
+        }
+    }
+}
+
+impl From<LyError> for SrCallbackError {
+    fn from(err: LyError) -> Self {
+        Self {
+            code: SrError::Ly as i32,
+            message: Some(err.to_string()),
+        }
+    }
+}
+
 /// Log level.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub enum SrLogLevel {
@@ -133,6 +246,7 @@ pub enum SrSubcribeFlag {
     Enabled = sr_subscr_flag_t_SR_SUBSCR_ENABLED as isize,
     Update = sr_subscr_flag_t_SR_SUBSCR_UPDATE as isize,
     OperMerge = sr_subscr_flag_t_SR_SUBSCR_OPER_MERGE as isize,
+    OperPollDiff = sr_subscr_flag_t_SR_SUBSCR_OPER_POLL_DIFF as isize,
 }
 
 /// Event.
@@ -250,6 +364,264 @@ pub enum LydAnyDataValueType {
     Lyb = LYD_ANYDATA_VALUETYPE_LYD_ANYDATA_LYB as isize,
 }
 
+/// Ly Context Flag.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LyCtxFlag {
+    AllImplemented = LY_CTX_ALL_IMPLEMENTED as isize,
+    RefImplemented = LY_CTX_REF_IMPLEMENTED as isize,
+    NoYangLibrary = LY_CTX_NO_YANGLIBRARY as isize,
+    DisableSearchDirs = LY_CTX_DISABLE_SEARCHDIRS as isize,
+    DisableSearchDirCwd = LY_CTX_DISABLE_SEARCHDIR_CWD as isize,
+    PreferSearchDirs = LY_CTX_PREFER_SEARCHDIRS as isize,
+}
+
+/// Lys Node Type.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LysNodeType {
+    Container = LYS_CONTAINER as isize,
+    Choice = LYS_CHOICE as isize,
+    Leaf = LYS_LEAF as isize,
+    LeafList = LYS_LEAFLIST as isize,
+    List = LYS_LIST as isize,
+    AnyXml = LYS_ANYXML as isize,
+    AnyData = LYS_ANYDATA as isize,
+    Case = LYS_CASE as isize,
+    Rpc = LYS_RPC as isize,
+    Action = LYS_ACTION as isize,
+    Notification = LYS_NOTIF as isize,
+    Input = LYS_INPUT as isize,
+    Output = LYS_OUTPUT as isize,
+}
+
+impl TryFrom<u32> for LysNodeType {
+    type Error = &'static str;
+
+    fn try_from(t: u32) -> Result<Self, Self::Error> {
+        match t {
+            LYS_CONTAINER => Ok(LysNodeType::Container),
+            LYS_CHOICE => Ok(LysNodeType::Choice),
+            LYS_LEAF => Ok(LysNodeType::Leaf),
+            LYS_LEAFLIST => Ok(LysNodeType::LeafList),
+            LYS_LIST => Ok(LysNodeType::List),
+            LYS_ANYXML => Ok(LysNodeType::AnyXml),
+            LYS_ANYDATA => Ok(LysNodeType::AnyData),
+            LYS_CASE => Ok(LysNodeType::Case),
+            LYS_RPC => Ok(LysNodeType::Rpc),
+            LYS_ACTION => Ok(LysNodeType::Action),
+            LYS_NOTIF => Ok(LysNodeType::Notification),
+            LYS_INPUT => Ok(LysNodeType::Input),
+            LYS_OUTPUT => Ok(LysNodeType::Output),
+            _ => Err("Invalid LysNodeType"),
+        }
+    }
+}
+
+impl fmt::Display for LysNodeType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            LysNodeType::Container => "container",
+            LysNodeType::Choice => "choice",
+            LysNodeType::Leaf => "leaf",
+            LysNodeType::LeafList => "leaf-list",
+            LysNodeType::List => "list",
+            LysNodeType::AnyXml => "anyxml",
+            LysNodeType::AnyData => "anydata",
+            LysNodeType::Case => "case",
+            LysNodeType::Rpc => "rpc",
+            LysNodeType::Action => "action",
+            LysNodeType::Notification => "notification",
+            LysNodeType::Input => "input",
+            LysNodeType::Output => "output",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Ly Data Type, i.e. YANG built-in type.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LyDataType {
+    Unknown = LY_DATA_TYPE_LY_TYPE_UNKNOWN as isize,
+    Binary = LY_DATA_TYPE_LY_TYPE_BINARY as isize,
+    UInt8 = LY_DATA_TYPE_LY_TYPE_UINT8 as isize,
+    UInt16 = LY_DATA_TYPE_LY_TYPE_UINT16 as isize,
+    UInt32 = LY_DATA_TYPE_LY_TYPE_UINT32 as isize,
+    UInt64 = LY_DATA_TYPE_LY_TYPE_UINT64 as isize,
+    String = LY_DATA_TYPE_LY_TYPE_STRING as isize,
+    Bits = LY_DATA_TYPE_LY_TYPE_BITS as isize,
+    Bool = LY_DATA_TYPE_LY_TYPE_BOOL as isize,
+    Decimal64 = LY_DATA_TYPE_LY_TYPE_DEC64 as isize,
+    Empty = LY_DATA_TYPE_LY_TYPE_EMPTY as isize,
+    Enum = LY_DATA_TYPE_LY_TYPE_ENUM as isize,
+    IdentityRef = LY_DATA_TYPE_LY_TYPE_IDENT as isize,
+    InstanceId = LY_DATA_TYPE_LY_TYPE_INST as isize,
+    LeafRef = LY_DATA_TYPE_LY_TYPE_LEAFREF as isize,
+    Union = LY_DATA_TYPE_LY_TYPE_UNION as isize,
+    Int8 = LY_DATA_TYPE_LY_TYPE_INT8 as isize,
+    Int16 = LY_DATA_TYPE_LY_TYPE_INT16 as isize,
+    Int32 = LY_DATA_TYPE_LY_TYPE_INT32 as isize,
+    Int64 = LY_DATA_TYPE_LY_TYPE_INT64 as isize,
+}
+
+impl TryFrom<u32> for LyDataType {
+    type Error = &'static str;
+
+    fn try_from(t: u32) -> Result<Self, Self::Error> {
+        match t {
+            LY_DATA_TYPE_LY_TYPE_UNKNOWN => Ok(LyDataType::Unknown),
+            LY_DATA_TYPE_LY_TYPE_BINARY => Ok(LyDataType::Binary),
+            LY_DATA_TYPE_LY_TYPE_UINT8 => Ok(LyDataType::UInt8),
+            LY_DATA_TYPE_LY_TYPE_UINT16 => Ok(LyDataType::UInt16),
+            LY_DATA_TYPE_LY_TYPE_UINT32 => Ok(LyDataType::UInt32),
+            LY_DATA_TYPE_LY_TYPE_UINT64 => Ok(LyDataType::UInt64),
+            LY_DATA_TYPE_LY_TYPE_STRING => Ok(LyDataType::String),
+            LY_DATA_TYPE_LY_TYPE_BITS => Ok(LyDataType::Bits),
+            LY_DATA_TYPE_LY_TYPE_BOOL => Ok(LyDataType::Bool),
+            LY_DATA_TYPE_LY_TYPE_DEC64 => Ok(LyDataType::Decimal64),
+            LY_DATA_TYPE_LY_TYPE_EMPTY => Ok(LyDataType::Empty),
+            LY_DATA_TYPE_LY_TYPE_ENUM => Ok(LyDataType::Enum),
+            LY_DATA_TYPE_LY_TYPE_IDENT => Ok(LyDataType::IdentityRef),
+            LY_DATA_TYPE_LY_TYPE_INST => Ok(LyDataType::InstanceId),
+            LY_DATA_TYPE_LY_TYPE_LEAFREF => Ok(LyDataType::LeafRef),
+            LY_DATA_TYPE_LY_TYPE_UNION => Ok(LyDataType::Union),
+            LY_DATA_TYPE_LY_TYPE_INT8 => Ok(LyDataType::Int8),
+            LY_DATA_TYPE_LY_TYPE_INT16 => Ok(LyDataType::Int16),
+            LY_DATA_TYPE_LY_TYPE_INT32 => Ok(LyDataType::Int32),
+            LY_DATA_TYPE_LY_TYPE_INT64 => Ok(LyDataType::Int64),
+            _ => Err("Invalid LyDataType"),
+        }
+    }
+}
+
+impl fmt::Display for LyDataType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            LyDataType::Unknown => "unknown",
+            LyDataType::Binary => "binary",
+            LyDataType::UInt8 => "uint8",
+            LyDataType::UInt16 => "uint16",
+            LyDataType::UInt32 => "uint32",
+            LyDataType::UInt64 => "uint64",
+            LyDataType::String => "string",
+            LyDataType::Bits => "bits",
+            LyDataType::Bool => "boolean",
+            LyDataType::Decimal64 => "decimal64",
+            LyDataType::Empty => "empty",
+            LyDataType::Enum => "enumeration",
+            LyDataType::IdentityRef => "identityref",
+            LyDataType::InstanceId => "instance-identifier",
+            LyDataType::LeafRef => "leafref",
+            LyDataType::Union => "union",
+            LyDataType::Int8 => "int8",
+            LyDataType::Int16 => "int16",
+            LyDataType::Int32 => "int32",
+            LyDataType::Int64 => "int64",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Lys Output Format.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LysOutFormat {
+    Yang = LYS_OUTFORMAT_LYS_OUT_YANG as isize,
+    YangCompiled = LYS_OUTFORMAT_LYS_OUT_YANG_COMPILED as isize,
+    Yin = LYS_OUTFORMAT_LYS_OUT_YIN as isize,
+    Tree = LYS_OUTFORMAT_LYS_OUT_TREE as isize,
+}
+
+/// Lys Print Flag.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LysPrintFlag {
+    Shrink = LYS_PRINT_SHRINK as isize,
+    NoSubstmt = LYS_PRINT_NO_SUBSTMT as isize,
+}
+
+/// Lys Input Format.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LysInFormat {
+    Yang = LYS_INFORMAT_LYS_IN_YANG as isize,
+    Yin = LYS_INFORMAT_LYS_IN_YIN as isize,
+}
+
+/// Lyd Format.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LydFormat {
+    Xml = LYD_FORMAT_LYD_XML as isize,
+    Json = LYD_FORMAT_LYD_JSON as isize,
+    Lyb = LYD_FORMAT_LYD_LYB as isize,
+}
+
+/// Lyd Print Flag.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LydPrintFlag {
+    WithSiblings = LYD_PRINT_WITHSIBLINGS as isize,
+    Shrink = LYD_PRINT_SHRINK as isize,
+    KeepEmptyCont = LYD_PRINT_KEEPEMPTYCONT as isize,
+    WdExplicit = LYD_PRINT_WD_EXPLICIT as isize,
+    WdTrim = LYD_PRINT_WD_TRIM as isize,
+    WdAll = LYD_PRINT_WD_ALL as isize,
+    WdAllTag = LYD_PRINT_WD_ALL_TAG as isize,
+    WdImplTag = LYD_PRINT_WD_IMPL_TAG as isize,
+}
+
+/// Lyd Parse Flag.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LydParseFlag {
+    Only = LYD_PARSE_ONLY as isize,
+    Strict = LYD_PARSE_STRICT as isize,
+    Opaq = LYD_PARSE_OPAQ as isize,
+    NoState = LYD_PARSE_NO_STATE as isize,
+    LybModUpdate = LYD_PARSE_LYB_MOD_UPDATE as isize,
+    Ordered = LYD_PARSE_ORDERED as isize,
+}
+
+/// Lyd Validate Flag.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LydValidateFlag {
+    NoState = LYD_VALIDATE_NO_STATE as isize,
+    Present = LYD_VALIDATE_PRESENT as isize,
+}
+
+/// Lyd Diff Flag.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LydDiffFlag {
+    Defaults = LYD_DIFF_DEFAULTS as isize,
+}
+
+/// Lyd Merge Flag.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LydMergeFlag {
+    Destruct = LYD_MERGE_DESTRUCT as isize,
+    Defaults = LYD_MERGE_DEFAULTS as isize,
+}
+
+/// Lyd Dup Flag.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LydDupFlag {
+    Recursive = LYD_DUP_RECURSIVE as isize,
+    NoMeta = LYD_DUP_NO_META as isize,
+    WithParents = LYD_DUP_WITH_PARENTS as isize,
+    WithFlags = LYD_DUP_WITH_FLAGS as isize,
+}
+
+/// Lyd Compare Flag.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LydCompareFlag {
+    FullRecursion = LYD_COMPARE_FULL_RECURSION as isize,
+    Defaults = LYD_COMPARE_DEFAULTS as isize,
+}
+
+/// Lyd Operation Type.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LydOpType {
+    RpcYang = lyd_type_LYD_TYPE_RPC_YANG as isize,
+    NotifYang = lyd_type_LYD_TYPE_NOTIF_YANG as isize,
+    ReplyYang = lyd_type_LYD_TYPE_REPLY_YANG as isize,
+    RpcNetconf = lyd_type_LYD_TYPE_RPC_NETCONF as isize,
+    NotifNetconf = lyd_type_LYD_TYPE_NOTIF_NETCONF as isize,
+    ReplyNetconf = lyd_type_LYD_TYPE_REPLY_NETCONF as isize,
+}
+
 /// Typedefs.
 pub type SrSessionId = *const sr_session_ctx_t;
 pub type SrSubscrId = *const sr_subscription_ctx_t;
@@ -370,6 +742,71 @@ pub fn log_syslog(app_name: &str, log_level: SrLogLevel) {
     }
 }
 
+/// Convert SystemTime to raw timespec.
+fn system_time_to_timespec(time: SystemTime) -> timespec {
+    let (sec, nsec) = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos() as i64),
+        Err(e) => {
+            let d = e.duration();
+            if d.subsec_nanos() == 0 {
+                (-(d.as_secs() as i64), 0)
+            } else {
+                (
+                    -(d.as_secs() as i64) - 1,
+                    1_000_000_000 - d.subsec_nanos() as i64,
+                )
+            }
+        }
+    };
+
+    timespec {
+        tv_sec: sec as _,
+        tv_nsec: nsec as _,
+    }
+}
+
+/// Convert raw timespec to SystemTime.
+fn timespec_to_system_time(ts: &timespec) -> SystemTime {
+    let sec = ts.tv_sec as i64;
+    let nsec = ts.tv_nsec as u32;
+
+    if sec >= 0 {
+        UNIX_EPOCH + Duration::new(sec as u64, nsec)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(sec.unsigned_abs()) + Duration::from_nanos(nsec as u64)
+    }
+}
+
+/// Convert SystemTime to YANG date-and-time string.
+pub fn system_time_to_date_and_time(time: SystemTime) -> Result<String, i32> {
+    let ts = system_time_to_timespec(time);
+    let mut s: *mut c_char = std::ptr::null_mut();
+
+    let rc = unsafe { ly_time_ts2str(&ts, &mut s) };
+    if rc != LY_ERR_LY_SUCCESS {
+        Err(rc as i32)
+    } else {
+        let date_and_time = unsafe { CStr::from_ptr(s).to_string_lossy().into_owned() };
+        unsafe {
+            libc::free(s as *mut c_void);
+        }
+        Ok(date_and_time)
+    }
+}
+
+/// Convert YANG date-and-time string to SystemTime.
+pub fn date_and_time_to_system_time(date_and_time: &str) -> Result<SystemTime, i32> {
+    let date_and_time = CString::new(date_and_time).map_err(|_| SrError::InvalArg as i32)?;
+    let mut ts = unsafe { zeroed::<timespec>() };
+
+    let rc = unsafe { ly_time_str2ts(date_and_time.as_ptr(), &mut ts) };
+    if rc != LY_ERR_LY_SUCCESS {
+        Err(rc as i32)
+    } else {
+        Ok(timespec_to_system_time(&ts))
+    }
+}
+
 /// Sysrepo connection.
 pub struct SrConn {
     /// Raw Pointer to Connection.
@@ -436,6 +873,233 @@ impl SrConn {
     pub fn get_context(&mut self) -> LibYangCtx {
         LibYangCtx::from(unsafe { sr_acquire_context(self.conn) })
     }
+
+    /// Install modules from schema files, with features to enable per module.
+    ///  Imports are searched in given directories.
+    pub fn install_modules(
+        &mut self,
+        paths: &[&str],
+        search_dirs: &[&str],
+        features: &[&[&str]],
+    ) -> Result<(), i32> {
+        let paths: Vec<CString> = paths.iter().map(|p| CString::new(*p).unwrap()).collect();
+        let mut path_ptrs: Vec<*const c_char> = paths.iter().map(|p| p.as_ptr()).collect();
+        path_ptrs.push(std::ptr::null());
+        let search_dirs = CString::new(search_dirs.join(":")).unwrap();
+
+        // NULL terminated feature array per module, NULL if none.
+        let features: Vec<Vec<CString>> = features
+            .iter()
+            .map(|f| f.iter().map(|f| CString::new(*f).unwrap()).collect())
+            .collect();
+        let mut feature_ptrs: Vec<Vec<*const c_char>> = features
+            .iter()
+            .map(|f| {
+                let mut ptrs: Vec<*const c_char> = f.iter().map(|f| f.as_ptr()).collect();
+                ptrs.push(std::ptr::null());
+                ptrs
+            })
+            .collect();
+        let mut module_features: Vec<*mut *const c_char> = (0..paths.len())
+            .map(|i| match feature_ptrs.get_mut(i) {
+                Some(ptrs) => ptrs.as_mut_ptr(),
+                None => std::ptr::null_mut(),
+            })
+            .collect();
+
+        let rc = unsafe {
+            sr_install_modules(
+                self.conn,
+                path_ptrs.as_mut_ptr(),
+                search_dirs.as_ptr(),
+                module_features.as_mut_ptr(),
+            )
+        };
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set initial startup and running data of installed module.
+    pub fn install_module_data(
+        &mut self,
+        mod_name: &str,
+        data: &str,
+        format: LydFormat,
+    ) -> Result<(), i32> {
+        let mod_name = CString::new(mod_name).unwrap();
+        let data = CString::new(data).unwrap();
+
+        let rc = unsafe {
+            sr_install_module_data(
+                self.conn,
+                mod_name.as_ptr(),
+                data.as_ptr(),
+                std::ptr::null(),
+                format as u32,
+            )
+        };
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Remove modules, force removes also modules depending on them.
+    pub fn remove_modules(&mut self, names: &[&str], force: bool) -> Result<(), i32> {
+        let names: Vec<CString> = names.iter().map(|n| CString::new(*n).unwrap()).collect();
+        let mut name_ptrs: Vec<*const c_char> = names.iter().map(|n| n.as_ptr()).collect();
+        name_ptrs.push(std::ptr::null());
+
+        let rc = unsafe { sr_remove_modules(self.conn, name_ptrs.as_mut_ptr(), force as i32) };
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Update modules to newer revisions from schema files.
+    pub fn update_modules(&mut self, paths: &[&str], search_dirs: &[&str]) -> Result<(), i32> {
+        let paths: Vec<CString> = paths.iter().map(|p| CString::new(*p).unwrap()).collect();
+        let mut path_ptrs: Vec<*const c_char> = paths.iter().map(|p| p.as_ptr()).collect();
+        path_ptrs.push(std::ptr::null());
+        let search_dirs = CString::new(search_dirs.join(":")).unwrap();
+
+        let rc =
+            unsafe { sr_update_modules(self.conn, path_ptrs.as_mut_ptr(), search_dirs.as_ptr()) };
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set owner, group and permissions of module in datastore.
+    ///  Those not given are left unchanged.
+    pub fn set_module_access(
+        &mut self,
+        mod_name: &str,
+        ds: SrDatastore,
+        owner: Option<&str>,
+        group: Option<&str>,
+        perm: Option<u32>,
+    ) -> Result<(), i32> {
+        let mod_name = CString::new(mod_name).unwrap();
+        let owner = owner.map(|owner| CString::new(owner).unwrap());
+        let group = group.map(|group| CString::new(group).unwrap());
+
+        let rc = unsafe {
+            sr_set_module_ds_access(
+                self.conn,
+                mod_name.as_ptr(),
+                ds as i32,
+                owner
+                    .as_ref()
+                    .map_or(std::ptr::null(), |owner| owner.as_ptr()),
+                group
+                    .as_ref()
+                    .map_or(std::ptr::null(), |group| group.as_ptr()),
+                perm.map_or(mode_t::MAX, |perm| perm as mode_t),
+            )
+        };
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Enable feature of module.
+    pub fn enable_module_feature(&mut self, mod_name: &str, feature: &str) -> Result<(), i32> {
+        let mod_name = CString::new(mod_name).unwrap();
+        let feature = CString::new(feature).unwrap();
+
+        let rc =
+            unsafe { sr_enable_module_feature(self.conn, mod_name.as_ptr(), feature.as_ptr()) };
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Disable feature of module.
+    pub fn disable_module_feature(&mut self, mod_name: &str, feature: &str) -> Result<(), i32> {
+        let mod_name = CString::new(mod_name).unwrap();
+        let feature = CString::new(feature).unwrap();
+
+        let rc =
+            unsafe { sr_disable_module_feature(self.conn, mod_name.as_ptr(), feature.as_ptr()) };
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get all features of installed module with their enabled flag.
+    pub fn get_module_features(&mut self, mod_name: &str) -> Result<Vec<(String, bool)>, i32> {
+        let ctx = unsafe { sr_acquire_context(self.conn) };
+        let features = LibYangCtx::from(ctx)
+            .get_module_implemented(mod_name)
+            .map(|module| module.features());
+        unsafe {
+            sr_release_context(self.conn);
+        }
+
+        features.ok_or(SrError::NotFound as i32)
+    }
+
+    /// Get names of enabled features of installed module.
+    pub fn get_enabled_features(&mut self, mod_name: &str) -> Result<Vec<String>, i32> {
+        Ok(self
+            .get_module_features(mod_name)?
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name)
+            .collect())
+    }
+
+    /// Get owner, group and permissions of module in datastore.
+    pub fn get_module_access(
+        &mut self,
+        mod_name: &str,
+        ds: SrDatastore,
+    ) -> Result<(String, String, u32), i32> {
+        let mod_name = CString::new(mod_name).unwrap();
+        let mut owner: *mut c_char = std::ptr::null_mut();
+        let mut group: *mut c_char = std::ptr::null_mut();
+        let mut perm: mode_t = 0;
+
+        let rc = unsafe {
+            sr_get_module_ds_access(
+                self.conn,
+                mod_name.as_ptr(),
+                ds as i32,
+                &mut owner,
+                &mut group,
+                &mut perm,
+            )
+        };
+        if rc != SrError::Ok as i32 {
+            return Err(rc);
+        }
+
+        unsafe {
+            let access = (
+                CStr::from_ptr(owner).to_string_lossy().into_owned(),
+                CStr::from_ptr(group).to_string_lossy().into_owned(),
+                perm as u32,
+            );
+            libc::free(owner as *mut c_void);
+            libc::free(group as *mut c_void);
+            Ok(access)
+        }
+    }
 }
 
 impl Drop for SrConn {
@@ -455,6 +1119,12 @@ pub struct SrSession {
 
     /// Map from raw pointer to subscription.
     subscrs: HashMap<SrSubscrId, SrSubscr>,
+
+    /// Xpaths of operational data pushed by this session, not applied yet.
+    oper_pending: Vec<String>,
+
+    /// Xpaths of operational data pushed by this session and applied.
+    oper_pushed: Vec<String>,
 }
 
 impl SrSession {
@@ -464,6 +1134,8 @@ impl SrSession {
             sess: std::ptr::null_mut(),
             owned: true,
             subscrs: HashMap::new(),
+            oper_pending: Vec::new(),
+            oper_pushed: Vec::new(),
         }
     }
 
@@ -473,6 +1145,8 @@ impl SrSession {
             sess: sess,
             owned: owned,
             subscrs: HashMap::new(),
+            oper_pending: Vec::new(),
+            oper_pushed: Vec::new(),
         }
     }
 
@@ -482,6 +1156,8 @@ impl SrSession {
             sess: self.sess,
             owned: false,
             subscrs: HashMap::new(),
+            oper_pending: Vec::new(),
+            oper_pushed: Vec::new(),
         }
     }
 
@@ -490,6 +1166,20 @@ impl SrSession {
         self.sess
     }
 
+    /// Set error message reported to the originator of the callback event.
+    pub fn set_error_message(&mut self, message: &str) -> Result<(), i32> {
+        let message = CString::new(message).unwrap();
+        let format = CString::new("%s").unwrap();
+
+        let rc =
+            unsafe { sr_session_set_error_message(self.sess, format.as_ptr(), message.as_ptr()) };
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Insert subscription.
     pub fn insert_subscription(&mut self, subscr: SrSubscr) -> SrSubscrId {
         let id = subscr.id();
@@ -540,14 +1230,26 @@ impl SrSession {
         origin: Option<&str>,
         opts: u32,
     ) -> Result<(), i32> {
-        let path = path.as_ptr() as *const i8;
-        let value = value.as_ptr() as *const i8;
-        let origin = match origin {
-            Some(orig) => orig.as_ptr() as *const i8,
-            None => std::ptr::null(),
-        };
+        let path = CString::new(path).unwrap();
+        let value = CString::new(value).unwrap();
+        let origin = origin.map(|orig| CString::new(orig).unwrap());
+        let origin = origin
+            .as_ref()
+            .map_or(std::ptr::null(), |orig| orig.as_ptr());
+
+        let rc = unsafe { sr_set_item_str(self.sess, path.as_ptr(), value.as_ptr(), origin, opts) };
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Delete item at given Xpath.
+    pub fn delete_item(&mut self, path: &str, opts: u32) -> Result<(), i32> {
+        let path = CString::new(path).unwrap();
 
-        let rc = unsafe { sr_set_item_str(self.sess, path, value, origin, opts) };
+        let rc = unsafe { sr_delete_item(self.sess, path.as_ptr(), opts) };
         if rc != SrError::Ok as i32 {
             Err(rc)
         } else {
@@ -563,27 +1265,126 @@ impl SrSession {
         if rc != SrError::Ok as i32 {
             Err(rc)
         } else {
+            self.oper_pushed.append(&mut self.oper_pending);
             Ok(())
         }
     }
 
-    /// Subscribe event notification.
+    /// Push operational data item, session must be on operational datastore.
+    ///  Item is stored by `apply_changes` and owned by this session until discarded.
+    pub fn oper_push_item_str(
+        &mut self,
+        path: &str,
+        value: &str,
+        origin: Option<&str>,
+        opts: u32,
+    ) -> Result<(), i32> {
+        if unsafe { sr_session_get_ds(self.sess) } != sr_datastore_t_SR_DS_OPERATIONAL {
+            return Err(SrError::InvalArg as i32);
+        }
+
+        self.set_item_str(path, value, origin, opts)?;
+        self.oper_pending.push(path.to_string());
+
+        Ok(())
+    }
+
+    /// Return Xpaths of operational data pushed by this session and stored by `apply_changes`.
+    pub fn oper_pushed(&self) -> &[String] {
+        &self.oper_pushed
+    }
+
+    /// Discard all operational data pushed by this session, stored or not applied yet.
+    pub fn discard_oper_changes(&mut self, timeout: Option<Duration>) -> Result<(), i32> {
+        let timeout_ms = timeout.map_or(0, |timeout| timeout.as_millis() as u32);
+
+        let rc = unsafe { sr_discard_changes(self.sess) };
+        if rc != SrError::Ok as i32 {
+            return Err(rc);
+        }
+        self.oper_pending.clear();
+
+        let rc = unsafe {
+            sr_discard_oper_changes(
+                sr_session_get_connection(self.sess),
+                self.sess,
+                std::ptr::null(),
+                timeout_ms,
+            )
+        };
+        if rc != SrError::Ok as i32 {
+            return Err(rc);
+        }
+        self.oper_pushed.clear();
+
+        Ok(())
+    }
+
+    /// Subscribe oper poll, caching data of oper get subscription for valid period.
+    ///  With diff set, changes of the cached data are reported as module changes.
+    pub fn oper_poll_subscribe(
+        &mut self,
+        mod_name: &str,
+        path: &str,
+        valid: Duration,
+        diff: bool,
+        opts: sr_subscr_options_t,
+    ) -> Result<&mut SrSubscr, i32> {
+        let mut subscr: *mut sr_subscription_ctx_t =
+            unsafe { zeroed::<*mut sr_subscription_ctx_t>() };
+        let mod_name = CString::new(mod_name).unwrap();
+        let path = CString::new(path).unwrap();
+        let valid_ms = valid.as_millis() as u32;
+        let opts = if diff {
+            opts | SrSubcribeFlag::OperPollDiff as sr_subscr_options_t
+        } else {
+            opts
+        };
+
+        let rc = unsafe {
+            sr_oper_poll_subscribe(
+                self.sess,
+                mod_name.as_ptr(),
+                path.as_ptr(),
+                valid_ms,
+                opts,
+                &mut subscr,
+            )
+        };
+
+        if rc != SrError::Ok as i32 {
+            Err(rc)
+        } else {
+            let id = self.insert_subscription(SrSubscr::from(subscr));
+            Ok(self.subscrs.get_mut(&id).unwrap())
+        }
+    }
+
+    /// Subscribe event notification.
+    ///  Start time and stop time delimit the replay window, if any.
+    ///  Callback gets notification timestamp, none if sysrepo gives no timestamp.
     pub fn notif_subscribe<F>(
         &mut self,
         mod_name: &str,
         xpath: Option<String>,
-        start_time: Option<*mut timespec>,
-        stop_time: Option<*mut timespec>,
+        start_time: Option<SystemTime>,
+        stop_time: Option<SystemTime>,
         callback: F,
         opts: sr_subscr_options_t,
     ) -> Result<&mut SrSubscr, i32>
     where
-        F: FnMut(SrSession, u32, SrNotifType, &str, SrValueSlice, *mut timespec) + 'static,
+        F: FnMut(SrSession, u32, SrNotifType, &str, SrValueSlice, Option<SystemTime>) + 'static,
     {
         let mod_name = mod_name.as_ptr() as *const i8;
         let xpath = xpath.map_or(std::ptr::null_mut(), |xpath| xpath.as_ptr() as *mut i8);
-        let start_time = start_time.unwrap_or(std::ptr::null_mut());
-        let stop_time = stop_time.unwrap_or(std::ptr::null_mut());
+        let mut start_time = start_time.map(system_time_to_timespec);
+        let mut stop_time = stop_time.map(system_time_to_timespec);
+        let start_time = start_time
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |ts| ts as *mut timespec);
+        let stop_time = stop_time
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |ts| ts as *mut timespec);
 
         let mut subscr: *mut sr_subscription_ctx_t =
             unsafe { zeroed::<*mut sr_subscription_ctx_t>() };
@@ -620,7 +1421,7 @@ impl SrSession {
         timestamp: *mut timespec,
         private_data: *mut c_void,
     ) where
-        F: FnMut(SrSession, u32, SrNotifType, &str, SrValueSlice, *mut timespec),
+        F: FnMut(SrSession, u32, SrNotifType, &str, SrValueSlice, Option<SystemTime>),
     {
         let callback_ptr = private_data as *mut F;
         let callback = &mut *callback_ptr;
@@ -629,6 +1430,11 @@ impl SrSession {
         let sr_values = SrValueSlice::from(values as *mut sr_val_t, values_cnt, false);
         let sess = SrSession::from(sess, false);
         let notif_type = SrNotifType::try_from(notif_type).expect("Convert error");
+        let timestamp = if timestamp.is_null() {
+            None
+        } else {
+            Some(timespec_to_system_time(&*timestamp))
+        };
 
         callback(sess, sub_id, notif_type, path, sr_values, timestamp);
     }
@@ -713,6 +1519,9 @@ impl SrSession {
     }
 
     /// Subscribe oper get items.
+    ///  Callback gets existing parent node if any, under which data can be created.
+    ///  Returned tree becomes parent if there is none, or is inserted as its child.
+    ///  Returning error fails the request of the originator with its message.
     pub fn oper_get_subscribe<F>(
         &mut self,
         mod_name: &str,
@@ -721,19 +1530,29 @@ impl SrSession {
         opts: sr_subscr_options_t,
     ) -> Result<&mut SrSubscr, i32>
     where
-        F: FnMut(&LibYangCtx, u32, &str, &str, Option<&str>, u32) -> Option<LydNode> + 'static,
+        F: FnMut(
+                SrSession,
+                &LibYangCtx,
+                u32,
+                &str,
+                &str,
+                Option<&str>,
+                u32,
+                Option<LydNodeRef>,
+            ) -> Result<Option<LydNode>, SrCallbackError>
+            + 'static,
     {
         let mut subscr: *mut sr_subscription_ctx_t =
             unsafe { zeroed::<*mut sr_subscription_ctx_t>() };
         let data = Box::into_raw(Box::new(callback));
-        let mod_name = mod_name.as_ptr() as *mut i8;
-        let path = path.as_ptr() as *mut i8;
+        let mod_name = CString::new(mod_name).unwrap();
+        let path = CString::new(path).unwrap();
 
         let rc = unsafe {
             sr_oper_get_subscribe(
                 self.sess,
-                mod_name,
-                path,
+                mod_name.as_ptr(),
+                path.as_ptr(),
                 Some(SrSession::call_get_items::<F>),
                 data as *mut _,
                 opts,
@@ -760,12 +1579,22 @@ impl SrSession {
         private_data: *mut c_void,
     ) -> i32
     where
-        F: FnMut(&LibYangCtx, u32, &str, &str, Option<&str>, u32) -> Option<LydNode>,
+        F: FnMut(
+            SrSession,
+            &LibYangCtx,
+            u32,
+            &str,
+            &str,
+            Option<&str>,
+            u32,
+            Option<LydNodeRef>,
+        ) -> Result<Option<LydNode>, SrCallbackError>,
     {
         let callback_ptr = private_data as *mut F;
         let callback = &mut *callback_ptr;
 
-        let ctx = sr_acquire_context(sr_session_get_connection(sess));
+        let conn = sr_session_get_connection(sess);
+        let ctx = sr_acquire_context(conn);
 
         let mod_name = CStr::from_ptr(mod_name).to_str().unwrap();
         let path = CStr::from_ptr(path).to_str().unwrap();
@@ -776,16 +1605,40 @@ impl SrSession {
         };
 
         let ctx = LibYangCtx::from(ctx);
-        let node = callback(&ctx, sub_id, mod_name, path, request_xpath, request_id);
+        let parent_node = if (*parent).is_null() {
+            None
+        } else {
+            Some(LydNodeRef::from(*parent))
+        };
+        let node = callback(
+            SrSession::from(sess, false),
+            &ctx,
+            sub_id,
+            mod_name,
+            path,
+            request_xpath,
+            request_id,
+            parent_node,
+        );
+
+        sr_release_context(conn);
 
         match node {
-            Some(node) => {
-                *parent = node.get_node();
+            Ok(Some(node)) => {
+                if (*parent).is_null() {
+                    *parent = node.into_raw();
+                } else {
+                    let rc = lyd_insert_child(*parent, node.get_node());
+                    if rc != LY_ERR_LY_SUCCESS {
+                        return sr_error_t_SR_ERR_LY as i32;
+                    }
+                    node.into_inserted();
+                }
+                sr_error_t_SR_ERR_OK as i32
             }
-            None => {}
+            Ok(None) => sr_error_t_SR_ERR_OK as i32,
+            Err(err) => err.report(&mut SrSession::from(sess, false)),
         }
-
-        sr_error_t_SR_ERR_OK as i32
     }
 
     /// Subscribe module change.
@@ -856,6 +1709,223 @@ impl SrSession {
         sr_error_t_SR_ERR_OK as i32
     }
 
+    /// Subscribe module change with transaction-aware handler.
+    ///  Changes are collected on Change or Enabled event and handed to `prepare`, the
+    ///  returned state is kept per request ID until Done or Abort event arrives.
+    pub fn module_change_subscribe_handler<H>(
+        &mut self,
+        mod_name: &str,
+        path: Option<&str>,
+        handler: H,
+        priority: u32,
+        opts: sr_subscr_options_t,
+    ) -> Result<&mut SrSubscr, i32>
+    where
+        H: ChangeHandler + 'static,
+    {
+        let changes_path = match path {
+            Some(path) => format!("{}//.", path),
+            None => format!("/{}:*//.", mod_name),
+        };
+        let data = Box::into_raw(Box::new(ChangeHandlerData {
+            handler: handler,
+            changes_path: changes_path,
+            states: HashMap::new(),
+        }));
+
+        let mut subscr: *mut sr_subscription_ctx_t =
+            unsafe { zeroed::<*mut sr_subscription_ctx_t>() };
+        let mod_name = CString::new(mod_name).unwrap();
+        let path = path.map(|path| CString::new(path).unwrap());
+        let path = path.as_ref().map_or(std::ptr::null(), |path| path.as_ptr());
+
+        let rc = unsafe {
+            sr_module_change_subscribe(
+                self.sess,
+                mod_name.as_ptr(),
+                path,
+                Some(SrSession::call_module_change_handler::<H>),
+                data as *mut _,
+                priority,
+                opts,
+                &mut subscr,
+            )
+        };
+
+        if rc != SrError::Ok as i32 {
+            unsafe {
+                drop(Box::from_raw(data));
+            }
+            Err(rc)
+        } else {
+            let id = self.insert_subscription(SrSubscr::from(subscr));
+            Ok(self.subscrs.get_mut(&id).unwrap())
+        }
+    }
+
+    unsafe extern "C" fn call_module_change_handler<H>(
+        sess: *mut sr_session_ctx_t,
+        _sub_id: u32,
+        _mod_name: *const c_char,
+        _path: *const c_char,
+        event: sr_event_t,
+        request_id: u32,
+        private_data: *mut c_void,
+    ) -> i32
+    where
+        H: ChangeHandler,
+    {
+        let data = &mut *(private_data as *mut ChangeHandlerData<H>);
+        let mut sess = SrSession::from(sess, false);
+
+        let event = match SrEvent::try_from(event) {
+            Ok(event) => event,
+            Err(_) => return sr_error_t_SR_ERR_INTERNAL as i32,
+        };
+
+        match event {
+            SrEvent::Change | SrEvent::Enabled => {
+                let changes = match sess.get_changes(&data.changes_path) {
+                    Ok(changes) => changes,
+                    Err(rc) => return rc,
+                };
+
+                match data.handler.prepare(&changes) {
+                    Ok(state) => {
+                        data.states.insert(request_id, state);
+                    }
+                    Err(rc) => return rc,
+                }
+            }
+            SrEvent::Done => {
+                let state = match data.states.remove(&request_id) {
+                    Some(state) => state,
+                    // No Change event seen, i.e. subscribed with DoneOnly.
+                    None => {
+                        let changes = match sess.get_changes(&data.changes_path) {
+                            Ok(changes) => changes,
+                            Err(rc) => return rc,
+                        };
+
+                        match data.handler.prepare(&changes) {
+                            Ok(state) => state,
+                            Err(rc) => return rc,
+                        }
+                    }
+                };
+
+                data.handler.commit(state);
+            }
+            SrEvent::Abort => {
+                // Abort for a request never prepared here is ignored.
+                if let Some(state) = data.states.remove(&request_id) {
+                    data.handler.abort(state);
+                }
+            }
+            SrEvent::Update | SrEvent::Rpc => {}
+        }
+
+        sr_error_t_SR_ERR_OK as i32
+    }
+
+    /// Subscribe module change Update event.
+    ///  Callback may amend the edit in flight through given update session,
+    ///  returning error rejects the whole change.
+    pub fn module_update_subscribe<F>(
+        &mut self,
+        mod_name: &str,
+        path: Option<&str>,
+        callback: F,
+        priority: u32,
+        opts: sr_subscr_options_t,
+    ) -> Result<&mut SrSubscr, i32>
+    where
+        F: FnMut(&mut SrUpdateSession, u32, &str, Option<&str>, u32) -> Result<(), i32> + 'static,
+    {
+        let mut subscr: *mut sr_subscription_ctx_t =
+            unsafe { zeroed::<*mut sr_subscription_ctx_t>() };
+        let data = Box::into_raw(Box::new(callback));
+        let mod_name = CString::new(mod_name).unwrap();
+        let path = path.map(|path| CString::new(path).unwrap());
+        let path = path.as_ref().map_or(std::ptr::null(), |path| path.as_ptr());
+        let opts = opts | SrSubcribeFlag::Update as sr_subscr_options_t;
+
+        let rc = unsafe {
+            sr_module_change_subscribe(
+                self.sess,
+                mod_name.as_ptr(),
+                path,
+                Some(SrSession::call_module_update::<F>),
+                data as *mut _,
+                priority,
+                opts,
+                &mut subscr,
+            )
+        };
+
+        if rc != SrError::Ok as i32 {
+            unsafe {
+                drop(Box::from_raw(data));
+            }
+            Err(rc)
+        } else {
+            let id = self.insert_subscription(SrSubscr::from(subscr));
+            Ok(self.subscrs.get_mut(&id).unwrap())
+        }
+    }
+
+    unsafe extern "C" fn call_module_update<F>(
+        sess: *mut sr_session_ctx_t,
+        sub_id: u32,
+        mod_name: *const c_char,
+        path: *const c_char,
+        event: sr_event_t,
+        request_id: u32,
+        private_data: *mut c_void,
+    ) -> i32
+    where
+        F: FnMut(&mut SrUpdateSession, u32, &str, Option<&str>, u32) -> Result<(), i32>,
+    {
+        // Other events of the same subscription are not of interest.
+        if event != sr_event_t_SR_EV_UPDATE {
+            return sr_error_t_SR_ERR_OK as i32;
+        }
+
+        let callback_ptr = private_data as *mut F;
+        let callback = &mut *callback_ptr;
+
+        let mod_name = CStr::from_ptr(mod_name).to_str().unwrap();
+        let path = if path == std::ptr::null_mut() {
+            None
+        } else {
+            Some(CStr::from_ptr(path).to_str().unwrap())
+        };
+        let mut update = SrUpdateSession {
+            sess: SrSession::from(sess, false),
+        };
+
+        match callback(&mut update, sub_id, mod_name, path, request_id) {
+            Ok(()) => sr_error_t_SR_ERR_OK as i32,
+            Err(rc) => rc,
+        }
+    }
+
+    /// Get all changes under given Xpath.
+    pub fn get_changes(&mut self, path: &str) -> Result<Vec<SrChange>, i32> {
+        let mut iter = self.get_changes_iter(path)?;
+        let mut changes = Vec::new();
+
+        while let Some((oper, old_value, new_value)) = self.get_change_next(&mut iter) {
+            changes.push(SrChange {
+                oper: oper,
+                old_value: old_value,
+                new_value: new_value,
+            });
+        }
+
+        Ok(changes)
+    }
+
     /// Get changes iter.
     pub fn get_changes_iter(&self, path: &str) -> Result<SrChangeIter, i32> {
         let mut it = unsafe { zeroed::<*mut sr_change_iter_t>() };
@@ -1020,90 +2090,2851 @@ impl Drop for SrChangeIter {
     }
 }
 
-/// Lib Yang Context.
-///  It just holds raw pointer, but does not own the object.
-pub struct LibYangCtx {
-    /// Raw Pointer to Lib Yang Context.
-    ly_ctx: *const ly_ctx,
+/// Session handed to Update event callback.
+///  Edits made through it are merged into the transaction in flight.
+pub struct SrUpdateSession {
+    /// Unowned callback session.
+    sess: SrSession,
 }
 
-impl LibYangCtx {
-    /// Constructo from raw pointer.
-    pub fn from(ly_ctx: *const ly_ctx) -> Self {
-        Self { ly_ctx: ly_ctx }
+impl SrUpdateSession {
+    /// Set string item to given Xpath in the edit in flight.
+    pub fn set_item_str(
+        &mut self,
+        path: &str,
+        value: &str,
+        origin: Option<&str>,
+        opts: u32,
+    ) -> Result<(), i32> {
+        self.sess.set_item_str(path, value, origin, opts)
     }
 
-    pub fn get_ctx(&self) -> *const ly_ctx {
-        self.ly_ctx
+    /// Delete item at given Xpath in the edit in flight.
+    pub fn delete_item(&mut self, path: &str, opts: u32) -> Result<(), i32> {
+        self.sess.delete_item(path, opts)
+    }
+
+    /// Get changes of the edit in flight under given Xpath.
+    pub fn get_changes(&mut self, path: &str) -> Result<Vec<SrChange>, i32> {
+        self.sess.get_changes(path)
+    }
+
+    /// Get underlying callback session.
+    pub fn session(&mut self) -> &mut SrSession {
+        &mut self.sess
     }
 }
 
-/// LibYang data node.
-pub struct LydNode {
-    /// Raw pointer to LibYang data node.
-    node: *mut lyd_node,
+/// Single change of a module change event.
+pub struct SrChange {
+    /// Change operation.
+    pub oper: SrChangeOper,
 
-    // /// Value.
-    // value: Option<LydValue>,
+    /// Old value, may hold null pointer.
+    pub old_value: SrValue,
+
+    /// New value, may hold null pointer.
+    pub new_value: SrValue,
 }
 
-impl LydNode {
-    pub fn from(node: *mut lyd_node) -> Self {
-        Self {
-            node: node,
-            // value: None,
-        }
-    }
+/// Transaction-aware module change handler.
+pub trait ChangeHandler {
+    /// State kept between Change and Done or Abort of the same request.
+    type State;
 
-    pub fn get_node(&self) -> *mut lyd_node {
-        self.node
-    }
+    /// Verify and prepare changes, error code is returned to sysrepo to reject them.
+    fn prepare(&mut self, changes: &[SrChange]) -> Result<Self::State, i32>;
 
-    pub fn free_all(&self) {
-        unsafe {
-            lyd_free_all(self.node);
-        }
-    }
+    /// Changes have been applied.
+    fn commit(&mut self, state: Self::State);
 
-    pub fn free_siblings(&self) {
-        unsafe {
-            lyd_free_siblings(self.node);
-        }
-    }
+    /// Changes have been rejected by another subscriber.
+    fn abort(&mut self, state: Self::State);
+}
 
-    pub fn free_tree(&self) {
-        unsafe {
-            lyd_free_tree(self.node);
-        }
-    }
+/// Private data of module change handler subscription.
+struct ChangeHandlerData<H: ChangeHandler> {
+    /// Handler.
+    handler: H,
+
+    /// Xpath to collect changes.
+    changes_path: String,
+
+    /// Map from request ID to prepared state.
+    states: HashMap<u32, H::State>,
 }
 
-/// LibYang data value.
-pub struct LydValue {
-    value_type: LydAnyDataValueType,
+/// Lib Yang Context.
+///  Context from sysrepo just holds raw pointer, but does not own the object.
+///  Standalone context is shared with data trees created in it, and destroyed
+///  when the last of them is dropped.
+pub struct LibYangCtx {
+    /// Raw Pointer to Lib Yang Context.
+    ly_ctx: *const ly_ctx,
 
-    /// TBD: It is string for now.
-    ///      It has to be variable length of byte array.
-    value: CString,
+    /// Owner of standalone context.
+    owner: Option<Rc<LyCtxOwner>>,
 }
 
-impl LydValue {
-    pub fn from_string(s: String) -> Self {
-        Self {
-            value_type: LydAnyDataValueType::String,
-            value: CString::new(s).unwrap(),
+/// Owner of standalone context, destroys it on drop.
+struct LyCtxOwner {
+    /// Raw Pointer to Lib Yang Context.
+    ly_ctx: *mut ly_ctx,
+}
+
+impl Drop for LyCtxOwner {
+    fn drop(&mut self) {
+        unsafe {
+            ly_ctx_destroy(self.ly_ctx);
         }
     }
+}
 
-    pub fn get_value(&self) -> &CStr {
-        &self.value
+impl LibYangCtx {
+    /// Constructo from raw pointer.
+    pub fn from(ly_ctx: *const ly_ctx) -> Self {
+        Self {
+            ly_ctx: ly_ctx,
+            owner: None,
+        }
     }
 
-    pub fn get_value_raw(&self) -> *mut c_void {
-        self.get_value() as *const _ as *mut c_void
-    }
+    /// Create standalone context, independent of sysrepo, with search directories.
+    pub fn new(search_dirs: &[&str], opts: u32) -> Result<LibYangCtx, LyError> {
+        let mut ctx: *mut ly_ctx = std::ptr::null_mut();
 
-    pub fn get_type(&self) -> LydAnyDataValueType {
+        let rc = unsafe { ly_ctx_new(std::ptr::null(), opts as u16, &mut ctx) };
+        if rc != LY_ERR_LY_SUCCESS {
+            return Err(LyError::from_message(
+                rc,
+                "Failed to create context".to_string(),
+                None,
+            ));
+        }
+
+        let ctx = LibYangCtx {
+            ly_ctx: ctx,
+            owner: Some(Rc::new(LyCtxOwner { ly_ctx: ctx })),
+        };
+        for dir in search_dirs {
+            let dir = CString::new(*dir).unwrap();
+            let rc = unsafe { ly_ctx_set_searchdir(ctx.ly_ctx as *mut ly_ctx, dir.as_ptr()) };
+            if rc != LY_ERR_LY_SUCCESS {
+                return Err(ctx.take_errors(rc));
+            }
+        }
+
+        Ok(ctx)
+    }
+
+    pub fn get_ctx(&self) -> *const ly_ctx {
+        self.ly_ctx
+    }
+
+    /// Take ownership of data tree created in this context, sharing the context with it.
+    fn new_tree(&self, node: *mut lyd_node) -> LydNode {
+        LydNode {
+            node: node,
+            ctx: self.ly_ctx,
+            owner: self.owner.clone(),
+        }
+    }
+
+    /// Resolve data path against the schema, predicates are ignored.
+    pub fn check_path(&self, path: &str) -> Result<LyscNode<'_>, LyError> {
+        let schema_path = CString::new(strip_predicates(path)).unwrap();
+
+        self.clear_errors();
+        let node = unsafe { lys_find_path(self.ly_ctx, std::ptr::null(), schema_path.as_ptr(), 0) };
+
+        match LyscNode::from_ptr(node) {
+            Some(node) => Ok(node),
+            None => {
+                let err = self.take_errors(LY_ERR_LY_ENOTFOUND);
+                if err.items.is_empty() {
+                    Err(LyError::from_message(
+                        LY_ERR_LY_ENOTFOUND,
+                        "Schema node not found".to_string(),
+                        Some(path.to_string()),
+                    ))
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    /// Resolve XPath, i.e. of a subscription, against the schema and return matching
+    ///  schema nodes. Each branch of union matching no schema node is reported as error.
+    pub fn check_xpath(&self, xpath: &str) -> Result<Vec<LyscNode<'_>>, LyError> {
+        let mut nodes: Vec<LyscNode> = Vec::new();
+
+        for branch in xpath_split_union(xpath) {
+            let found = self.find_xpath_schema(branch)?;
+            if found.is_empty() {
+                return Err(LyError::from_message(
+                    LY_ERR_LY_ENOTFOUND,
+                    "XPath matches no schema node".to_string(),
+                    Some(branch.to_string()),
+                ));
+            }
+
+            for node in found {
+                if !nodes.iter().any(|n| n.node == node.node) {
+                    nodes.push(node);
+                }
+            }
+        }
+
+        Ok(nodes)
+    }
+
+    /// Return schema nodes matching XPath.
+    fn find_xpath_schema(&self, xpath: &str) -> Result<Vec<LyscNode<'_>>, LyError> {
+        let xpath = CString::new(xpath).unwrap();
+        let mut set: *mut ly_set = std::ptr::null_mut();
+
+        self.clear_errors();
+        let rc =
+            unsafe { lys_find_xpath(self.ly_ctx, std::ptr::null(), xpath.as_ptr(), 0, &mut set) };
+        if rc != LY_ERR_LY_SUCCESS {
+            return Err(self.take_errors(rc));
+        }
+
+        let nodes = unsafe {
+            let snodes = (*set).__bindgen_anon_1.snodes;
+            let nodes = (0..(*set).count as usize)
+                .map(|i| LyscNode::from(*snodes.add(i)))
+                .collect();
+            ly_set_free(set, None);
+            nodes
+        };
+        self.clear_errors();
+
+        Ok(nodes)
+    }
+
+    /// Validate value for leaf or leaf-list at given data path and return its
+    ///  canonical form. Leafref and instance-identifier targets are not checked.
+    pub fn validate_value(&self, path: &str, value: &str) -> Result<String, LyError> {
+        let node = self.check_path(path)?;
+        if !matches!(node.kind(), LysNodeType::Leaf | LysNodeType::LeafList) {
+            return Err(LyError::from_message(
+                LY_ERR_LY_EINVAL,
+                "Schema node is not leaf or leaf-list".to_string(),
+                Some(path.to_string()),
+            ));
+        }
+
+        let mut canonical: *const c_char = std::ptr::null();
+
+        self.clear_errors();
+        let rc = unsafe {
+            lyd_value_validate(
+                self.ly_ctx,
+                node.get_node(),
+                value.as_ptr() as *const c_char,
+                value.len() as _,
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                &mut canonical,
+            )
+        };
+        if rc != LY_ERR_LY_SUCCESS && rc != LY_ERR_LY_EINCOMPLETE {
+            return Err(self.take_errors(rc));
+        }
+
+        if canonical.is_null() {
+            Ok(value.to_string())
+        } else {
+            let s = unsafe { CStr::from_ptr(canonical).to_string_lossy().into_owned() };
+            unsafe {
+                lydict_remove(self.ly_ctx, canonical);
+            }
+            Ok(s)
+        }
+    }
+
+    /// Find compiled schema node by schema path, in RPC or action output if set.
+    pub fn find_schema_path(&self, path: &str, output: bool) -> Option<LyscNode<'_>> {
+        let path = CString::new(path).unwrap();
+        let node = unsafe {
+            lys_find_path(
+                self.ly_ctx,
+                std::ptr::null(),
+                path.as_ptr(),
+                output as ly_bool,
+            )
+        };
+        self.clear_errors();
+
+        LyscNode::from_ptr(node)
+    }
+
+    /// Return iterator over all modules in the context.
+    pub fn modules(&self) -> LysModuleIter<'_> {
+        LysModuleIter {
+            ctx: self.ly_ctx,
+            index: 0,
+            _ctx: PhantomData,
+        }
+    }
+
+    /// Return module by name and revision, none for the one without revision.
+    pub fn get_module(&self, name: &str, revision: Option<&str>) -> Option<LysModule<'_>> {
+        let name = CString::new(name).unwrap();
+        let revision = revision.map(|revision| CString::new(revision).unwrap());
+        let module = unsafe {
+            ly_ctx_get_module(
+                self.ly_ctx,
+                name.as_ptr(),
+                revision
+                    .as_ref()
+                    .map_or(std::ptr::null(), |revision| revision.as_ptr()),
+            )
+        };
+
+        if module.is_null() {
+            None
+        } else {
+            Some(LysModule::from(module))
+        }
+    }
+
+    /// Return implemented module by name.
+    pub fn get_module_implemented(&self, name: &str) -> Option<LysModule<'_>> {
+        let name = CString::new(name).unwrap();
+        let module = unsafe { ly_ctx_get_module_implemented(self.ly_ctx, name.as_ptr()) };
+
+        if module.is_null() {
+            None
+        } else {
+            Some(LysModule::from(module))
+        }
+    }
+
+    /// Return latest revision of module by name.
+    pub fn get_module_latest(&self, name: &str) -> Option<LysModule<'_>> {
+        let name = CString::new(name).unwrap();
+        let module = unsafe { ly_ctx_get_module_latest(self.ly_ctx, name.as_ptr()) };
+
+        if module.is_null() {
+            None
+        } else {
+            Some(LysModule::from(module))
+        }
+    }
+
+    /// Find identity by identityref value "module:identity".
+    ///  Value without module prefix is looked up in default module.
+    pub fn get_identity(
+        &self,
+        value: &str,
+        default_module: Option<&str>,
+    ) -> Result<LyscIdent<'_>, LyError> {
+        let (module, name) = match split_identityref(value) {
+            (Some(module), name) => (module, name),
+            (None, name) => match default_module {
+                Some(module) => (module, name),
+                None => {
+                    return Err(LyError::from_message(
+                        LY_ERR_LY_EINVAL,
+                        format!("Identityref \"{}\" has no module prefix", value),
+                        None,
+                    ))
+                }
+            },
+        };
+
+        self.get_module_implemented(module)
+            .or_else(|| self.get_module_latest(module))
+            .and_then(|module| {
+                module
+                    .identities()
+                    .into_iter()
+                    .find(|ident| ident.name() == name)
+            })
+            .ok_or_else(|| {
+                LyError::from_message(
+                    LY_ERR_LY_ENOTFOUND,
+                    format!("Identity \"{}:{}\" not found", module, name),
+                    None,
+                )
+            })
+    }
+
+    /// Return all identities derived from base, directly or indirectly.
+    pub fn derived_identities(&self, base: &str) -> Result<Vec<LyscIdent<'_>>, LyError> {
+        Ok(self.get_identity(base, None)?.all_derived())
+    }
+
+    /// Return true if identity is derived from base, both given as "module:identity".
+    pub fn is_derived_from(&self, identity: &str, base: &str) -> Result<bool, LyError> {
+        let identity = self.get_identity(identity, None)?;
+        let base = self.get_identity(base, None)?;
+
+        Ok(identity.is_derived_from(&base))
+    }
+
+    /// Return true if identity is base itself or derived from it,
+    ///  as XPath function derived-from-or-self().
+    pub fn is_derived_from_or_self(&self, identity: &str, base: &str) -> Result<bool, LyError> {
+        let identity = self.get_identity(identity, None)?;
+        let base = self.get_identity(base, None)?;
+
+        Ok(identity == base || identity.is_derived_from(&base))
+    }
+
+    /// Return source of module in YANG or YIN format, i.e. for get-schema.
+    ///  Original file is returned if the module was parsed from one in the same
+    ///  format, otherwise the parsed module is printed. Latest revision is used
+    ///  if none is given.
+    pub fn get_schema(
+        &self,
+        name: &str,
+        revision: Option<&str>,
+        format: LysOutFormat,
+    ) -> Result<String, LyError> {
+        let module = match revision {
+            Some(_) => self.get_module(name, revision),
+            None => self.get_module_latest(name),
+        };
+        let module = match module {
+            Some(module) => module,
+            None => {
+                return Err(LyError::from_message(
+                    LY_ERR_LY_ENOTFOUND,
+                    format!("Module \"{}\" not found", name),
+                    None,
+                ))
+            }
+        };
+
+        let ext = match format {
+            LysOutFormat::Yang => Some(".yang"),
+            LysOutFormat::Yin => Some(".yin"),
+            _ => None,
+        };
+        if let (Some(ext), Some(filepath)) = (ext, module.filepath()) {
+            if filepath.ends_with(ext) {
+                if let Ok(source) = std::fs::read_to_string(filepath) {
+                    return Ok(source);
+                }
+            }
+        }
+
+        module.print_mem(format, 0)
+    }
+
+    /// Load module by name from search directories and implement it.
+    ///  Latest revision is loaded if none is given.
+    pub fn load_module(
+        &self,
+        name: &str,
+        revision: Option<&str>,
+        features: &[&str],
+    ) -> Result<LysModule<'_>, LyError> {
+        let name = CString::new(name).unwrap();
+        let revision = revision.map(|revision| CString::new(revision).unwrap());
+        let features: Vec<CString> = features
+            .iter()
+            .map(|feature| CString::new(*feature).unwrap())
+            .collect();
+        let mut features_ptr: Vec<*const c_char> = features.iter().map(|f| f.as_ptr()).collect();
+        features_ptr.push(std::ptr::null());
+
+        self.clear_errors();
+        let module = unsafe {
+            ly_ctx_load_module(
+                self.ly_ctx as *mut ly_ctx,
+                name.as_ptr(),
+                revision
+                    .as_ref()
+                    .map_or(std::ptr::null(), |revision| revision.as_ptr()),
+                features_ptr.as_mut_ptr(),
+            )
+        };
+
+        if module.is_null() {
+            Err(self.take_errors(LY_ERR_LY_ENOTFOUND))
+        } else {
+            Ok(LysModule::from(module))
+        }
+    }
+
+    /// Parse module from memory in YANG or YIN format and implement it.
+    pub fn parse_module_mem(
+        &self,
+        data: &str,
+        format: LysInFormat,
+        features: &[&str],
+    ) -> Result<LysModule<'_>, LyError> {
+        let data = CString::new(data).unwrap();
+        let features: Vec<CString> = features
+            .iter()
+            .map(|feature| CString::new(*feature).unwrap())
+            .collect();
+        let mut features_ptr: Vec<*const c_char> = features.iter().map(|f| f.as_ptr()).collect();
+        features_ptr.push(std::ptr::null());
+        let mut input: *mut ly_in = std::ptr::null_mut();
+        let mut module: *mut lys_module = std::ptr::null_mut();
+
+        self.clear_errors();
+        let rc = unsafe { ly_in_new_memory(data.as_ptr(), &mut input) };
+        if rc != LY_ERR_LY_SUCCESS {
+            return Err(self.take_errors(rc));
+        }
+
+        let rc = unsafe {
+            lys_parse(
+                self.ly_ctx as *mut ly_ctx,
+                input,
+                format as u32,
+                features_ptr.as_mut_ptr(),
+                &mut module,
+            )
+        };
+        unsafe {
+            ly_in_free(input, 0);
+        }
+
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(self.take_errors(rc))
+        } else {
+            Ok(LysModule::from(module))
+        }
+    }
+
+    /// Parse module from file in YANG or YIN format and implement it.
+    pub fn parse_module_path(
+        &self,
+        path: &str,
+        format: LysInFormat,
+    ) -> Result<LysModule<'_>, LyError> {
+        let path = CString::new(path).unwrap();
+        let mut module: *mut lys_module = std::ptr::null_mut();
+
+        self.clear_errors();
+        let rc = unsafe {
+            lys_parse_path(
+                self.ly_ctx as *mut ly_ctx,
+                path.as_ptr(),
+                format as u32,
+                &mut module,
+            )
+        };
+
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(self.take_errors(rc))
+        } else {
+            Ok(LysModule::from(module))
+        }
+    }
+
+    /// Clear errors recorded in the context.
+    fn clear_errors(&self) {
+        unsafe {
+            ly_err_clean(self.ly_ctx as *mut ly_ctx, std::ptr::null_mut());
+        }
+    }
+
+    /// Collect and clear errors recorded in the context.
+    fn take_errors(&self, rc: LY_ERR) -> LyError {
+        let mut items = Vec::new();
+
+        unsafe {
+            let mut e = ly_err_first(self.ly_ctx);
+            while !e.is_null() {
+                let message = if (*e).msg.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr((*e).msg).to_string_lossy().into_owned()
+                };
+                let path = if (*e).path.is_null() {
+                    None
+                } else {
+                    Some(CStr::from_ptr((*e).path).to_string_lossy().into_owned())
+                };
+
+                items.push(LyErrorItem {
+                    code: (*e).no as i32,
+                    vecode: (*e).vecode as u32,
+                    message: message,
+                    path: path,
+                });
+                e = (*e).next;
+            }
+        }
+        self.clear_errors();
+
+        LyError {
+            code: rc as i32,
+            items: items,
+        }
+    }
+
+    /// Parse data tree from JSON, XML or LYB.
+    ///  Returns none if the data are empty.
+    pub fn parse_data(
+        &self,
+        data: &[u8],
+        format: LydFormat,
+        parse_opts: u32,
+        validate_opts: u32,
+    ) -> Result<Option<LydNode>, LyError> {
+        let mut buf = data.to_vec();
+        buf.push(0);
+        let mut tree: *mut lyd_node = std::ptr::null_mut();
+
+        self.clear_errors();
+        let rc = unsafe {
+            lyd_parse_data_mem(
+                self.ly_ctx,
+                buf.as_ptr() as *const c_char,
+                format as u32,
+                parse_opts,
+                validate_opts,
+                &mut tree,
+            )
+        };
+
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(self.take_errors(rc))
+        } else if tree.is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(self.new_tree(tree)))
+        }
+    }
+
+    /// Parse RPC, action, reply or notification, optionally into given parent.
+    ///  Returns the parsed tree, and the operation for NETCONF types, where it is
+    ///  a separate tree and the parsed one holds the opaque envelope. Otherwise the
+    ///  operation is part of the parsed tree. When parsed into parent, the operation
+    ///  is part of the parent and only the envelope of NETCONF types is returned.
+    pub fn parse_op(
+        &self,
+        parent: Option<&mut LydNodeMut>,
+        data: &[u8],
+        format: LydFormat,
+        op_type: LydOpType,
+    ) -> Result<(Option<LydNode>, Option<LydNode>), LyError> {
+        let mut buf = data.to_vec();
+        buf.push(0);
+        let parent = parent.map_or(std::ptr::null_mut(), |parent| parent.get_node());
+        let mut input: *mut ly_in = std::ptr::null_mut();
+        let mut tree: *mut lyd_node = std::ptr::null_mut();
+        let mut op: *mut lyd_node = std::ptr::null_mut();
+
+        let rc = unsafe { ly_in_new_memory(buf.as_ptr() as *const c_char, &mut input) };
+        if rc != LY_ERR_LY_SUCCESS {
+            return Err(self.take_errors(rc));
+        }
+
+        self.clear_errors();
+        let rc = unsafe {
+            lyd_parse_op(
+                self.ly_ctx,
+                parent,
+                input,
+                format as u32,
+                op_type as lyd_type,
+                &mut tree,
+                &mut op,
+            )
+        };
+        unsafe {
+            ly_in_free(input, 0);
+        }
+
+        if rc != LY_ERR_LY_SUCCESS {
+            return Err(self.take_errors(rc));
+        }
+
+        let netconf = matches!(
+            op_type,
+            LydOpType::RpcNetconf | LydOpType::NotifNetconf | LydOpType::ReplyNetconf
+        );
+        // Parsed tree is only set to the envelope when parsing into parent.
+        let tree = if tree.is_null() {
+            None
+        } else {
+            Some(self.new_tree(tree))
+        };
+        let op = if op.is_null() || !netconf || !parent.is_null() {
+            None
+        } else {
+            Some(self.new_tree(op))
+        };
+
+        Ok((tree, op))
+    }
+}
+
+/// LibYang schema module.
+///  It just holds raw pointer, module is owned by the context.
+pub struct LysModule<'a> {
+    /// Raw pointer to module.
+    module: *mut lys_module,
+
+    /// Context the module is borrowed from.
+    _ctx: PhantomData<&'a LibYangCtx>,
+}
+
+impl<'a> LysModule<'a> {
+    /// Wrap raw pointer, lifetime must be bound to the context by the caller.
+    pub(crate) fn from(module: *mut lys_module) -> Self {
+        Self {
+            module: module,
+            _ctx: PhantomData,
+        }
+    }
+
+    pub fn get_module(&self) -> *mut lys_module {
+        self.module
+    }
+
+    /// Return module name.
+    pub fn name(&self) -> &str {
+        unsafe { CStr::from_ptr((*self.module).name).to_str().unwrap() }
+    }
+
+    /// Return module revision, if any.
+    pub fn revision(&self) -> Option<&str> {
+        unsafe { cstr_to_option((*self.module).revision) }
+    }
+
+    /// Return module namespace.
+    pub fn namespace(&self) -> &str {
+        unsafe { CStr::from_ptr((*self.module).ns).to_str().unwrap() }
+    }
+
+    /// Return module prefix.
+    pub fn prefix(&self) -> &str {
+        unsafe { CStr::from_ptr((*self.module).prefix).to_str().unwrap() }
+    }
+
+    /// Return true if the module is implemented, not only imported.
+    pub fn is_implemented(&self) -> bool {
+        unsafe { (*self.module).implemented != 0 }
+    }
+
+    /// Return all features with their enabled flag, including those of submodules.
+    pub fn features(&self) -> Vec<(String, bool)> {
+        let mut features = Vec::new();
+
+        unsafe {
+            let pmod = (*self.module).parsed;
+            if pmod.is_null() {
+                return features;
+            }
+
+            let mut index: u32 = 0;
+            let mut feature = lysp_feature_next(std::ptr::null(), pmod, &mut index);
+            while !feature.is_null() {
+                let name = CStr::from_ptr((*feature).name)
+                    .to_string_lossy()
+                    .into_owned();
+                let enabled = (*feature).flags as u32 & LYS_FENABLED != 0;
+                features.push((name, enabled));
+                feature = lysp_feature_next(feature, pmod, &mut index);
+            }
+        }
+
+        features
+    }
+
+    /// Return names of enabled features.
+    pub fn enabled_features(&self) -> Vec<String> {
+        self.features()
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Return imported modules.
+    pub fn imports(&self) -> Vec<LysModule<'a>> {
+        unsafe {
+            let pmod = (*self.module).parsed;
+            if pmod.is_null() {
+                return Vec::new();
+            }
+
+            let imports = (*pmod).imports;
+            (0..ly_array_count(imports as *const c_void))
+                .map(|i| LysModule::from((*imports.add(i)).module))
+                .collect()
+        }
+    }
+
+    /// Print module in given format.
+    pub fn print_mem(&self, format: LysOutFormat, opts: u32) -> Result<String, LyError> {
+        let ctx = LibYangCtx::from(unsafe { (*self.module).ctx });
+        let mut buf: *mut c_char = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe { lys_print_mem(&mut buf, self.module, format as u32, opts) };
+        if rc != LY_ERR_LY_SUCCESS {
+            return Err(ctx.take_errors(rc));
+        }
+
+        let s = unsafe { CStr::from_ptr(buf).to_string_lossy().into_owned() };
+        unsafe {
+            libc::free(buf as *mut c_void);
+        }
+
+        Ok(s)
+    }
+
+    /// Return path of the file the module was parsed from, if any.
+    pub fn filepath(&self) -> Option<&str> {
+        unsafe { cstr_to_option((*self.module).filepath) }
+    }
+
+    /// Return iterator over top-level data nodes.
+    pub fn data_nodes(&self) -> LyscSiblingIter<'a> {
+        let first = unsafe {
+            let compiled = (*self.module).compiled;
+            if compiled.is_null() {
+                std::ptr::null()
+            } else {
+                (*compiled).data as *const lysc_node
+            }
+        };
+
+        LyscSiblingIter {
+            next: first,
+            _ctx: PhantomData,
+        }
+    }
+
+    /// Return iterator over RPCs.
+    pub fn rpcs(&self) -> LyscSiblingIter<'a> {
+        let first = unsafe {
+            let compiled = (*self.module).compiled;
+            if compiled.is_null() {
+                std::ptr::null()
+            } else {
+                (*compiled).rpcs as *const lysc_node
+            }
+        };
+
+        LyscSiblingIter {
+            next: first,
+            _ctx: PhantomData,
+        }
+    }
+
+    /// Return iterator over top-level notifications.
+    pub fn notifications(&self) -> LyscSiblingIter<'a> {
+        let first = unsafe {
+            let compiled = (*self.module).compiled;
+            if compiled.is_null() {
+                std::ptr::null()
+            } else {
+                (*compiled).notifs as *const lysc_node
+            }
+        };
+
+        LyscSiblingIter {
+            next: first,
+            _ctx: PhantomData,
+        }
+    }
+
+    /// Return typedefs defined in the module with their compiled types.
+    ///  Compiled type is none if the typedef is not used by any node.
+    pub fn typedefs(&self) -> Vec<(String, Option<LyscType<'a>>)> {
+        unsafe {
+            let pmod = (*self.module).parsed;
+            if pmod.is_null() {
+                return Vec::new();
+            }
+
+            let typedefs = (*pmod).typedefs;
+            (0..ly_array_count(typedefs as *const c_void))
+                .map(|i| {
+                    let typedef = &*typedefs.add(i);
+                    let name = CStr::from_ptr(typedef.name).to_string_lossy().into_owned();
+                    let ty = if typedef.type_.compiled.is_null() {
+                        None
+                    } else {
+                        Some(LyscType {
+                            ty: typedef.type_.compiled,
+                            _ctx: PhantomData,
+                        })
+                    };
+                    (name, ty)
+                })
+                .collect()
+        }
+    }
+
+    /// Return identities defined in the module.
+    pub fn identities(&self) -> Vec<LyscIdent<'a>> {
+        unsafe {
+            let identities = (*self.module).identities;
+            (0..ly_array_count(identities as *const c_void))
+                .map(|i| LyscIdent::from(identities.add(i)))
+                .collect()
+        }
+    }
+
+    /// Return included submodules with their revisions.
+    pub fn submodules(&self) -> Vec<(String, Option<String>)> {
+        unsafe {
+            let pmod = (*self.module).parsed;
+            if pmod.is_null() {
+                return Vec::new();
+            }
+
+            let includes = (*pmod).includes;
+            (0..ly_array_count(includes as *const c_void))
+                .map(|i| {
+                    let include = &*includes.add(i);
+                    let name = CStr::from_ptr(include.name).to_string_lossy().into_owned();
+                    let rev = CStr::from_ptr(include.rev.as_ptr()).to_string_lossy();
+                    let rev = if rev.is_empty() {
+                        None
+                    } else {
+                        Some(rev.into_owned())
+                    };
+                    (name, rev)
+                })
+                .collect()
+        }
+    }
+}
+
+/// LibYang schema module iterator.
+pub struct LysModuleIter<'a> {
+    /// Raw pointer to context.
+    ctx: *const ly_ctx,
+
+    /// Iterator index.
+    index: u32,
+
+    /// Context the modules are borrowed from.
+    _ctx: PhantomData<&'a LibYangCtx>,
+}
+
+impl<'a> Iterator for LysModuleIter<'a> {
+    type Item = LysModule<'a>;
+
+    fn next(&mut self) -> Option<LysModule<'a>> {
+        let module = unsafe { ly_ctx_get_module_iter(self.ctx, &mut self.index) };
+
+        if module.is_null() {
+            None
+        } else {
+            Some(LysModule::from(module))
+        }
+    }
+}
+
+/// Return count of libyang sized array, which is stored right before the items.
+unsafe fn ly_array_count(array: *const c_void) -> usize {
+    if array.is_null() {
+        0
+    } else {
+        *(array as *const u64).offset(-1) as usize
+    }
+}
+
+/// Strip predicates from data path, leaving schema path.
+fn strip_predicates(path: &str) -> String {
+    let mut schema_path = String::with_capacity(path.len());
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+
+    for c in path.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'') | (None, '"') if depth > 0 => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            (None, c) if depth == 0 => schema_path.push(c),
+            _ => {}
+        }
+    }
+
+    schema_path
+}
+
+/// Convert nullable C string to Option.
+unsafe fn cstr_to_option<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(s).to_str().unwrap())
+    }
+}
+
+/// LibYang compiled schema node.
+///  It just holds raw pointer, node is owned by the context.
+pub struct LyscNode<'a> {
+    /// Raw pointer to compiled schema node.
+    node: *const lysc_node,
+
+    /// Context the node is borrowed from.
+    _ctx: PhantomData<&'a LibYangCtx>,
+}
+
+impl<'a> LyscNode<'a> {
+    /// Wrap raw pointer, lifetime must be bound to the context by the caller.
+    pub(crate) fn from(node: *const lysc_node) -> Self {
+        Self {
+            node: node,
+            _ctx: PhantomData,
+        }
+    }
+
+    /// Wrap raw pointer, if not null.
+    fn from_ptr(node: *const lysc_node) -> Option<LyscNode<'a>> {
+        if node.is_null() {
+            None
+        } else {
+            Some(LyscNode::from(node))
+        }
+    }
+
+    pub fn get_node(&self) -> *const lysc_node {
+        self.node
+    }
+
+    fn nodetype(&self) -> u32 {
+        unsafe { (*self.node).nodetype as u32 }
+    }
+
+    fn flags(&self) -> u32 {
+        unsafe { (*self.node).flags as u32 }
+    }
+
+    fn ctx(&self) -> *const ly_ctx {
+        unsafe { (*(*self.node).module).ctx }
+    }
+
+    /// Return node kind.
+    pub fn kind(&self) -> LysNodeType {
+        LysNodeType::try_from(self.nodetype()).expect("Convert error")
+    }
+
+    /// Return node name.
+    pub fn name(&self) -> &str {
+        unsafe { CStr::from_ptr((*self.node).name).to_str().unwrap() }
+    }
+
+    /// Return module the node belongs to.
+    pub fn module(&self) -> LysModule<'a> {
+        LysModule::from(unsafe { (*self.node).module })
+    }
+
+    /// Return description, if any.
+    pub fn description(&self) -> Option<&str> {
+        unsafe { cstr_to_option((*self.node).dsc) }
+    }
+
+    /// Return reference, if any.
+    pub fn reference(&self) -> Option<&str> {
+        unsafe { cstr_to_option((*self.node).ref_) }
+    }
+
+    /// Return schema path of the node.
+    pub fn path(&self) -> String {
+        unsafe {
+            let path = lysc_path(
+                self.node,
+                LYSC_PATH_TYPE_LYSC_PATH_DATA,
+                std::ptr::null_mut(),
+                0,
+            );
+            let s = CStr::from_ptr(path).to_string_lossy().into_owned();
+            libc::free(path as *mut c_void);
+            s
+        }
+    }
+
+    /// Return parent node.
+    pub fn parent(&self) -> Option<LyscNode<'a>> {
+        LyscNode::from_ptr(unsafe { (*self.node).parent })
+    }
+
+    /// Return iterator over children.
+    pub fn children(&self) -> LyscSiblingIter<'a> {
+        LyscSiblingIter {
+            next: unsafe { lysc_node_child(self.node) },
+            _ctx: PhantomData,
+        }
+    }
+
+    /// Return depth-first iterator over this node and all its descendants.
+    pub fn traverse(&self) -> LyscTraverseIter<'a> {
+        LyscTraverseIter {
+            start: self.node,
+            next: self.node,
+            _ctx: PhantomData,
+        }
+    }
+
+    /// Return true if the node is configuration.
+    pub fn is_config(&self) -> bool {
+        self.flags() & LYS_CONFIG_W != 0
+    }
+
+    /// Return true if the node is state data.
+    pub fn is_state(&self) -> bool {
+        self.flags() & LYS_CONFIG_R != 0
+    }
+
+    /// Return true if the node is mandatory.
+    pub fn is_mandatory(&self) -> bool {
+        self.flags() & LYS_MAND_TRUE != 0
+    }
+
+    /// Return true if the node is list key.
+    pub fn is_key(&self) -> bool {
+        self.flags() & LYS_KEY != 0
+    }
+
+    /// Return true if list or leaf-list is ordered by user.
+    pub fn is_ordered_by_user(&self) -> bool {
+        self.flags() & LYS_ORDBY_USER != 0
+    }
+
+    /// Return default values of leaf or leaf-list, or default case of choice.
+    pub fn default_values(&self) -> Vec<String> {
+        let mut values = Vec::new();
+
+        unsafe {
+            match self.kind() {
+                LysNodeType::Leaf => {
+                    let dflt = (*(self.node as *const lysc_node_leaf)).dflt;
+                    if !dflt.is_null() {
+                        let value = lyd_value_get_canonical(self.ctx(), dflt);
+                        values.push(CStr::from_ptr(value).to_string_lossy().into_owned());
+                    }
+                }
+                LysNodeType::LeafList => {
+                    let dflts = (*(self.node as *const lysc_node_leaflist)).dflts;
+                    for i in 0..ly_array_count(dflts as *const c_void) {
+                        let value = lyd_value_get_canonical(self.ctx(), *dflts.add(i));
+                        values.push(CStr::from_ptr(value).to_string_lossy().into_owned());
+                    }
+                }
+                LysNodeType::Choice => {
+                    let dflt = (*(self.node as *const lysc_node_choice)).dflt;
+                    if !dflt.is_null() {
+                        let name = (*(dflt as *const lysc_node)).name;
+                        values.push(CStr::from_ptr(name).to_string_lossy().into_owned());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        values
+    }
+
+    /// Return key names of list.
+    pub fn list_keys(&self) -> Vec<String> {
+        if self.kind() != LysNodeType::List {
+            return Vec::new();
+        }
+
+        self.children()
+            .take_while(|child| child.is_key())
+            .map(|child| child.name().to_string())
+            .collect()
+    }
+
+    /// Return min and max elements of list or leaf-list, max is none if unbounded.
+    pub fn min_max_elements(&self) -> Option<(u32, Option<u32>)> {
+        let (min, max) = unsafe {
+            match self.kind() {
+                LysNodeType::List => {
+                    let list = &*(self.node as *const lysc_node_list);
+                    (list.min, list.max)
+                }
+                LysNodeType::LeafList => {
+                    let llist = &*(self.node as *const lysc_node_leaflist);
+                    (llist.min, llist.max)
+                }
+                _ => return None,
+            }
+        };
+
+        Some((min, if max == u32::MAX { None } else { Some(max) }))
+    }
+
+    /// Return type of leaf or leaf-list.
+    pub fn leaf_type(&self) -> Option<LyscType<'a>> {
+        let ty = unsafe {
+            match self.kind() {
+                LysNodeType::Leaf => (*(self.node as *const lysc_node_leaf)).type_,
+                LysNodeType::LeafList => (*(self.node as *const lysc_node_leaflist)).type_,
+                _ => return None,
+            }
+        };
+
+        Some(LyscType {
+            ty: ty,
+            _ctx: PhantomData,
+        })
+    }
+
+    /// Print schema subtree of the node in given format, tree format is wrapped at line length.
+    pub fn print(
+        &self,
+        format: LysOutFormat,
+        line_length: usize,
+        opts: u32,
+    ) -> Result<String, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let mut buf: *mut c_char = std::ptr::null_mut();
+        let mut out: *mut ly_out = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe { ly_out_new_memory(&mut buf, 0, &mut out) };
+        if rc != LY_ERR_LY_SUCCESS {
+            return Err(ctx.take_errors(rc));
+        }
+
+        let rc = unsafe { lys_print_node(out, self.node, format as u32, line_length as _, opts) };
+        let res = if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else if buf.is_null() {
+            Ok(String::new())
+        } else {
+            let len = unsafe { ly_out_printed(out) } as usize;
+            let buf = unsafe { slice::from_raw_parts(buf as *const u8, len) };
+            Ok(String::from_utf8_lossy(buf).into_owned())
+        };
+
+        unsafe {
+            ly_out_free(out, None, 1);
+        }
+
+        res
+    }
+
+    /// Return units of leaf or leaf-list, if any.
+    pub fn units(&self) -> Option<&str> {
+        unsafe {
+            match self.kind() {
+                LysNodeType::Leaf => cstr_to_option((*(self.node as *const lysc_node_leaf)).units),
+                LysNodeType::LeafList => {
+                    cstr_to_option((*(self.node as *const lysc_node_leaflist)).units)
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+/// LibYang compiled schema node sibling iterator.
+pub struct LyscSiblingIter<'a> {
+    /// Next node.
+    next: *const lysc_node,
+
+    /// Context the nodes are borrowed from.
+    _ctx: PhantomData<&'a LibYangCtx>,
+}
+
+impl<'a> Iterator for LyscSiblingIter<'a> {
+    type Item = LyscNode<'a>;
+
+    fn next(&mut self) -> Option<LyscNode<'a>> {
+        let node = LyscNode::from_ptr(self.next)?;
+        self.next = unsafe { (*self.next).next };
+        Some(node)
+    }
+}
+
+/// LibYang compiled schema node depth-first iterator.
+pub struct LyscTraverseIter<'a> {
+    /// Node the traversal started at.
+    start: *const lysc_node,
+
+    /// Next node.
+    next: *const lysc_node,
+
+    /// Context the nodes are borrowed from.
+    _ctx: PhantomData<&'a LibYangCtx>,
+}
+
+impl<'a> Iterator for LyscTraverseIter<'a> {
+    type Item = LyscNode<'a>;
+
+    fn next(&mut self) -> Option<LyscNode<'a>> {
+        let node = LyscNode::from_ptr(self.next)?;
+
+        // Descend to child, or go to next sibling of nearest ancestor.
+        self.next = unsafe {
+            let child = lysc_node_child(self.next);
+            if !child.is_null() {
+                child
+            } else {
+                let mut cur = self.next;
+                loop {
+                    if cur == self.start {
+                        break std::ptr::null();
+                    }
+                    if !(*cur).next.is_null() {
+                        break (*cur).next as *const lysc_node;
+                    }
+                    cur = (*cur).parent as *const lysc_node;
+                    if cur.is_null() {
+                        break std::ptr::null();
+                    }
+                }
+            }
+        };
+
+        Some(node)
+    }
+}
+
+/// LibYang compiled identity.
+///  It just holds raw pointer, identity is owned by the context.
+pub struct LyscIdent<'a> {
+    /// Raw pointer to compiled identity.
+    ident: *const lysc_ident,
+
+    /// Context the identity is borrowed from.
+    _ctx: PhantomData<&'a LibYangCtx>,
+}
+
+impl<'a> LyscIdent<'a> {
+    /// Wrap raw pointer, lifetime must be bound to the context by the caller.
+    pub(crate) fn from(ident: *const lysc_ident) -> Self {
+        Self {
+            ident: ident,
+            _ctx: PhantomData,
+        }
+    }
+
+    pub fn get_ident(&self) -> *const lysc_ident {
+        self.ident
+    }
+
+    /// Return identity name.
+    pub fn name(&self) -> &str {
+        unsafe { CStr::from_ptr((*self.ident).name).to_str().unwrap() }
+    }
+
+    /// Return module the identity belongs to.
+    pub fn module(&self) -> LysModule<'a> {
+        LysModule::from(unsafe { (*self.ident).module })
+    }
+
+    /// Return description, if any.
+    pub fn description(&self) -> Option<&str> {
+        unsafe { cstr_to_option((*self.ident).dsc) }
+    }
+
+    /// Return identities directly derived from this one.
+    pub fn derived(&self) -> Vec<LyscIdent<'a>> {
+        unsafe {
+            let derived = (*self.ident).derived;
+            (0..ly_array_count(derived as *const c_void))
+                .map(|i| LyscIdent::from(*derived.add(i)))
+                .collect()
+        }
+    }
+
+    /// Return all identities derived from this one, directly or indirectly.
+    pub fn all_derived(&self) -> Vec<LyscIdent<'a>> {
+        let mut all: Vec<LyscIdent> = Vec::new();
+        let mut stack = self.derived();
+
+        while let Some(ident) = stack.pop() {
+            if !all.contains(&ident) {
+                stack.extend(ident.derived());
+                all.push(ident);
+            }
+        }
+
+        all
+    }
+
+    /// Return true if this identity is derived from base, directly or indirectly.
+    pub fn is_derived_from(&self, base: &LyscIdent) -> bool {
+        base.all_derived().contains(self)
+    }
+
+    /// Return identity name qualified with module name, as in identityref value.
+    pub fn qualified_name(&self) -> String {
+        format!("{}:{}", self.module().name(), self.name())
+    }
+}
+
+impl<'a> PartialEq for LyscIdent<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ident == other.ident
+    }
+}
+
+/// Split identityref value into module and identity names, module is none if not prefixed.
+pub fn split_identityref(value: &str) -> (Option<&str>, &str) {
+    match value.split_once(':') {
+        Some((module, name)) => (Some(module), name),
+        None => (None, value),
+    }
+}
+
+/// LibYang compiled type.
+///  It just holds raw pointer, type is owned by the context.
+pub struct LyscType<'a> {
+    /// Raw pointer to compiled type.
+    ty: *const lysc_type,
+
+    /// Context the type is borrowed from.
+    _ctx: PhantomData<&'a LibYangCtx>,
+}
+
+impl<'a> LyscType<'a> {
+    pub fn get_type(&self) -> *const lysc_type {
+        self.ty
+    }
+
+    /// Return built-in base type.
+    pub fn base_type(&self) -> LyDataType {
+        LyDataType::try_from(unsafe { (*self.ty).basetype as u32 }).expect("Convert error")
+    }
+
+    /// Return built-in base type name.
+    pub fn base_type_name(&self) -> String {
+        self.base_type().to_string()
+    }
+
+    /// Return fraction digits of decimal64.
+    pub fn fraction_digits(&self) -> Option<u8> {
+        if self.base_type() == LyDataType::Decimal64 {
+            Some(unsafe { (*(self.ty as *const lysc_type_dec)).fraction_digits })
+        } else {
+            None
+        }
+    }
+
+    /// Return range of numeric types, or length of string and binary, as printable bounds.
+    pub fn ranges(&self) -> Vec<(String, String)> {
+        let base_type = self.base_type();
+        let range = unsafe {
+            match base_type {
+                LyDataType::Int8
+                | LyDataType::Int16
+                | LyDataType::Int32
+                | LyDataType::Int64
+                | LyDataType::UInt8
+                | LyDataType::UInt16
+                | LyDataType::UInt32
+                | LyDataType::UInt64 => (*(self.ty as *const lysc_type_num)).range,
+                LyDataType::Decimal64 => (*(self.ty as *const lysc_type_dec)).range,
+                LyDataType::String => (*(self.ty as *const lysc_type_str)).length,
+                LyDataType::Binary => (*(self.ty as *const lysc_type_bin)).length,
+                _ => std::ptr::null_mut(),
+            }
+        };
+        if range.is_null() {
+            return Vec::new();
+        }
+
+        let signed = matches!(
+            base_type,
+            LyDataType::Int8
+                | LyDataType::Int16
+                | LyDataType::Int32
+                | LyDataType::Int64
+                | LyDataType::Decimal64
+        );
+        let digits = self.fraction_digits().unwrap_or(0) as u32;
+        let bound = |b: i64| {
+            if digits == 0 {
+                b.to_string()
+            } else {
+                let scale = 10i64.pow(digits);
+                let sign = if b < 0 { "-" } else { "" };
+                format!(
+                    "{}{}.{:0width$}",
+                    sign,
+                    (b / scale).abs(),
+                    (b % scale).abs(),
+                    width = digits as usize
+                )
+            }
+        };
+
+        unsafe {
+            let parts = (*range).parts;
+            (0..ly_array_count(parts as *const c_void))
+                .map(|i| {
+                    let part = &*parts.add(i);
+                    if signed {
+                        (
+                            bound(part.__bindgen_anon_1.min_64),
+                            bound(part.__bindgen_anon_2.max_64),
+                        )
+                    } else {
+                        (
+                            part.__bindgen_anon_1.min_u64.to_string(),
+                            part.__bindgen_anon_2.max_u64.to_string(),
+                        )
+                    }
+                })
+                .collect()
+        }
+    }
+
+    /// Return patterns of string, inverted ones are marked by flag.
+    pub fn patterns(&self) -> Vec<(String, bool)> {
+        if self.base_type() != LyDataType::String {
+            return Vec::new();
+        }
+
+        unsafe {
+            let patterns = (*(self.ty as *const lysc_type_str)).patterns;
+            (0..ly_array_count(patterns as *const c_void))
+                .map(|i| {
+                    let pattern = &**patterns.add(i);
+                    (
+                        CStr::from_ptr(pattern.expr).to_string_lossy().into_owned(),
+                        pattern.inverted() != 0,
+                    )
+                })
+                .collect()
+        }
+    }
+
+    /// Return enum names and values.
+    pub fn enums(&self) -> Vec<(String, i32)> {
+        if self.base_type() != LyDataType::Enum {
+            return Vec::new();
+        }
+
+        unsafe {
+            let enums = (*(self.ty as *const lysc_type_enum)).enums;
+            (0..ly_array_count(enums as *const c_void))
+                .map(|i| {
+                    let item = &*enums.add(i);
+                    (
+                        CStr::from_ptr(item.name).to_string_lossy().into_owned(),
+                        item.__bindgen_anon_1.value,
+                    )
+                })
+                .collect()
+        }
+    }
+
+    /// Return bit names and positions.
+    pub fn bits(&self) -> Vec<(String, u32)> {
+        if self.base_type() != LyDataType::Bits {
+            return Vec::new();
+        }
+
+        unsafe {
+            let bits = (*(self.ty as *const lysc_type_bits)).bits;
+            (0..ly_array_count(bits as *const c_void))
+                .map(|i| {
+                    let item = &*bits.add(i);
+                    (
+                        CStr::from_ptr(item.name).to_string_lossy().into_owned(),
+                        item.__bindgen_anon_1.position,
+                    )
+                })
+                .collect()
+        }
+    }
+
+    /// Return member types of union.
+    pub fn union_types(&self) -> Vec<LyscType<'a>> {
+        if self.base_type() != LyDataType::Union {
+            return Vec::new();
+        }
+
+        unsafe {
+            let types = (*(self.ty as *const lysc_type_union)).types;
+            (0..ly_array_count(types as *const c_void))
+                .map(|i| LyscType {
+                    ty: *types.add(i),
+                    _ctx: PhantomData,
+                })
+                .collect()
+        }
+    }
+
+    /// Return resolved type of leafref target.
+    pub fn leafref_real_type(&self) -> Option<LyscType<'a>> {
+        if self.base_type() != LyDataType::LeafRef {
+            return None;
+        }
+
+        let ty = unsafe { (*(self.ty as *const lysc_type_leafref)).realtype };
+        if ty.is_null() {
+            None
+        } else {
+            Some(LyscType {
+                ty: ty,
+                _ctx: PhantomData,
+            })
+        }
+    }
+}
+
+/// LibYang data tree.
+///  It owns the root node, which is freed with all its siblings on drop.
+///  Tree created in standalone context keeps the context alive.
+///  Tree is empty when all its nodes are removed by diff or validation.
+pub struct LydNode {
+    /// Raw pointer to root node, null if the tree is empty.
+    node: *mut lyd_node,
+
+    /// Raw pointer to context of the tree.
+    ctx: *const ly_ctx,
+
+    /// Owner of standalone context the tree is created in.
+    owner: Option<Rc<LyCtxOwner>>,
+}
+
+impl LydNode {
+    /// Take ownership of data tree.
+    ///
+    /// # Safety
+    ///
+    /// The node must be a valid data tree not owned by anyone else, as it is
+    ///  freed with all its siblings on drop. Its context must outlive the tree.
+    pub unsafe fn from(node: *mut lyd_node) -> Self {
+        Self {
+            node: node,
+            ctx: LydNodeRef::from(node).ctx(),
+            owner: None,
+        }
+    }
+
+    /// Return raw pointer to root node, null if the tree is empty.
+    pub fn get_node(&self) -> *mut lyd_node {
+        self.node
+    }
+
+    /// Give up ownership and return raw pointer, i.e. when sysrepo takes the tree.
+    ///  Standalone context of the tree is then never destroyed, as the tree may
+    ///  still use it.
+    pub fn into_raw(self) -> *mut lyd_node {
+        mem::ManuallyDrop::new(self).node
+    }
+
+    /// Give up ownership of tree inserted into another tree of the same context,
+    ///  which keeps the context alive.
+    fn into_inserted(self) -> *mut lyd_node {
+        let mut tree = mem::ManuallyDrop::new(self);
+        tree.owner.take();
+        tree.node
+    }
+
+    /// Return root node borrowed from the tree, to navigate and query it,
+    ///  none if the tree is empty.
+    pub fn as_ref(&self) -> Option<LydNodeRef<'_>> {
+        if self.node.is_null() {
+            None
+        } else {
+            Some(self.node_ref(self.node))
+        }
+    }
+
+    /// Return raw context of the tree.
+    fn ctx(&self) -> *const ly_ctx {
+        self.ctx
+    }
+
+    /// Borrow node of this tree.
+    fn node_ref(&self, node: *mut lyd_node) -> LydNodeRef<'_> {
+        LydNodeRef {
+            node: node,
+            owner: self.owner.as_ref(),
+        }
+    }
+
+    /// Return root node borrowed exclusively from the tree, to modify it,
+    ///  none if the tree is empty.
+    pub fn as_mut(&mut self) -> Option<LydNodeMut<'_>> {
+        if self.node.is_null() {
+            None
+        } else {
+            Some(LydNodeMut {
+                node: self.node,
+                owner: self.owner.as_ref(),
+                _tree: PhantomData,
+            })
+        }
+    }
+
+    /// Find single node by data path and borrow it exclusively from the tree.
+    ///  Output nodes of RPC or action are searched when output is set.
+    pub fn find_path_mut(
+        &mut self,
+        path: &str,
+        output: bool,
+    ) -> Result<Option<LydNodeMut<'_>>, LyError> {
+        let node = match self.as_ref() {
+            Some(root) => root.find_path(path, output)?.map(|node| node.node),
+            None => None,
+        };
+
+        Ok(node.map(|node| LydNodeMut {
+            node: node,
+            owner: self.owner.as_ref(),
+            _tree: PhantomData,
+        }))
+    }
+
+    /// Create nodes by absolute data path in this tree, with value of the last one.
+    ///  Returns the first created node, none if all of them existed.
+    ///  Value of existing node is replaced with LYD_NEW_PATH_UPDATE option.
+    pub fn new_path(
+        &mut self,
+        path: &str,
+        value: Option<&LydValue>,
+        options: u32,
+    ) -> Result<Option<LydNodeRef<'_>>, i32> {
+        let node = LibYang::lyd_new_path_raw(self.node, self.ctx, path, value, options)?;
+        if self.node.is_null() {
+            self.node = node;
+        }
+
+        if node.is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(self.node_ref(node)))
+        }
+    }
+
+    /// Apply diff to this data tree.
+    ///  The root node may be replaced by another first sibling.
+    pub fn diff_apply_all(&mut self, diff: LydNodeRef) -> Result<(), LyError> {
+        let ctx = LibYangCtx::from(self.ctx);
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_diff_apply_all(&mut self.node, diff.node) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Merge source subtree into this data tree.
+    ///  Source is never consumed, Destruct flag is ignored.
+    pub fn merge_tree(&mut self, source: LydNodeRef, opts: u32) -> Result<(), LyError> {
+        let ctx = LibYangCtx::from(self.ctx);
+        let opts = opts & !(LydMergeFlag::Destruct as u32);
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_merge_tree(&mut self.node, source.node, opts as u16) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Merge source node with siblings into this data tree.
+    ///  Source is never consumed, Destruct flag is ignored.
+    pub fn merge_siblings(&mut self, source: LydNodeRef, opts: u32) -> Result<(), LyError> {
+        let ctx = LibYangCtx::from(self.ctx);
+        let opts = opts & !(LydMergeFlag::Destruct as u32);
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_merge_siblings(&mut self.node, source.node, opts as u16) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Validate this data tree with all its siblings, default nodes may be added.
+    ///  The root node may be replaced by another first sibling.
+    pub fn validate_all(&mut self, opts: u32) -> Result<(), LyError> {
+        let ctx = LibYangCtx::from(self.ctx);
+
+        ctx.clear_errors();
+        let rc =
+            unsafe { lyd_validate_all(&mut self.node, ctx.get_ctx(), opts, std::ptr::null_mut()) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Validate RPC or action input or output, or notification in this tree.
+    ///  References are resolved against given data tree, if any.
+    ///  Nodes of the operation may be removed or added.
+    pub fn validate_op(
+        &mut self,
+        dep_tree: Option<LydNodeRef>,
+        op_type: LydOpType,
+    ) -> Result<(), LyError> {
+        if self.node.is_null() {
+            return Err(LyError::from_message(
+                LY_ERR_LY_EINVAL,
+                "Data tree is empty".to_string(),
+                None,
+            ));
+        }
+        let ctx = LibYangCtx::from(self.ctx);
+        let dep_tree = dep_tree.map_or(std::ptr::null(), |tree| tree.get_node() as *const _);
+
+        ctx.clear_errors();
+        let rc = unsafe {
+            lyd_validate_op(
+                self.node,
+                dep_tree,
+                op_type as lyd_type,
+                std::ptr::null_mut(),
+            )
+        };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Drop for LydNode {
+    fn drop(&mut self) {
+        unsafe {
+            lyd_free_all(self.node);
+        }
+    }
+}
+
+/// LibYang data node.
+///  It just holds raw pointer, node is owned by the tree it is borrowed from,
+///  e.g. LydNode or a tree owned by sysrepo.
+#[derive(Clone, Copy)]
+pub struct LydNodeRef<'a> {
+    /// Raw pointer to LibYang data node.
+    node: *mut lyd_node,
+
+    /// Owner of standalone context, borrowed from the tree.
+    owner: Option<&'a Rc<LyCtxOwner>>,
+}
+
+impl<'a> LydNodeRef<'a> {
+    /// Borrow data node.
+    ///
+    /// # Safety
+    ///
+    /// The node must be valid and must not be freed for the lifetime of the reference.
+    ///  Its context must outlive trees duplicated from it.
+    pub unsafe fn from(node: *mut lyd_node) -> Self {
+        Self {
+            node: node,
+            owner: None,
+        }
+    }
+
+    pub fn get_node(&self) -> *mut lyd_node {
+        self.node
+    }
+
+    /// Borrow another node from the same tree.
+    fn node_ref(&self, node: *mut lyd_node) -> LydNodeRef<'a> {
+        LydNodeRef {
+            node: node,
+            owner: self.owner,
+        }
+    }
+
+    /// Borrow another node from the same tree, if not null.
+    fn opt_node_ref(&self, node: *mut lyd_node) -> Option<LydNodeRef<'a>> {
+        if node.is_null() {
+            None
+        } else {
+            Some(self.node_ref(node))
+        }
+    }
+
+    /// Take ownership of new data tree created from this node, sharing its context.
+    fn new_tree(&self, node: *mut lyd_node) -> LydNode {
+        LydNode {
+            node: node,
+            ctx: self.ctx(),
+            owner: self.owner.cloned(),
+        }
+    }
+
+    /// Return raw schema node, null for opaque node.
+    fn schema(&self) -> *const lysc_node {
+        unsafe { (*self.node).schema }
+    }
+
+    /// Return raw context the node belongs to.
+    fn ctx(&self) -> *const ly_ctx {
+        let schema = self.schema();
+        unsafe {
+            if schema.is_null() {
+                (*(self.node as *mut lyd_node_opaq)).ctx
+            } else {
+                (*(*schema).module).ctx as *const ly_ctx
+            }
+        }
+    }
+
+    /// Return true if the node is opaque, i.e. it has no schema.
+    pub fn is_opaque(&self) -> bool {
+        self.schema().is_null()
+    }
+
+    /// Return true if the node is leaf or leaf-list.
+    pub fn is_term(&self) -> bool {
+        let schema = self.schema();
+        !schema.is_null() && unsafe { (*schema).nodetype as u32 } & (LYS_LEAF | LYS_LEAFLIST) != 0
+    }
+
+    /// Return true if the node is list instance.
+    pub fn is_list(&self) -> bool {
+        let schema = self.schema();
+        !schema.is_null() && unsafe { (*schema).nodetype as u32 } & LYS_LIST != 0
+    }
+
+    /// Return parent node.
+    pub fn parent(&self) -> Option<LydNodeRef<'a>> {
+        self.opt_node_ref(unsafe { (*self.node).parent as *mut lyd_node })
+    }
+
+    /// Return first child node.
+    pub fn first_child(&self) -> Option<LydNodeRef<'a>> {
+        let schema = self.schema();
+        let child = unsafe {
+            if schema.is_null() {
+                (*(self.node as *mut lyd_node_opaq)).child
+            } else if (*schema).nodetype as u32
+                & (LYS_CONTAINER | LYS_LIST | LYS_RPC | LYS_ACTION | LYS_NOTIF)
+                != 0
+            {
+                (*(self.node as *mut lyd_node_inner)).child
+            } else {
+                std::ptr::null_mut()
+            }
+        };
+
+        self.opt_node_ref(child)
+    }
+
+    /// Return next sibling node.
+    pub fn next_sibling(&self) -> Option<LydNodeRef<'a>> {
+        self.opt_node_ref(unsafe { (*self.node).next })
+    }
+
+    /// Return previous sibling node.
+    pub fn prev_sibling(&self) -> Option<LydNodeRef<'a>> {
+        // Prev of the first sibling points to the last one.
+        let prev = unsafe { (*self.node).prev };
+        if prev == self.node || unsafe { (*prev).next.is_null() } {
+            None
+        } else {
+            self.opt_node_ref(prev)
+        }
+    }
+
+    /// Return first sibling node.
+    pub fn first_sibling(&self) -> LydNodeRef<'a> {
+        self.node_ref(unsafe { lyd_first_sibling(self.node) })
+    }
+
+    /// Return iterator over children.
+    pub fn children(&self) -> LydSiblingIter<'a> {
+        LydSiblingIter {
+            next: self.first_child(),
+        }
+    }
+
+    /// Return depth-first iterator over this node and all its descendants.
+    pub fn traverse(&self) -> LydTraverseIter<'a> {
+        LydTraverseIter {
+            start: *self,
+            next: Some(*self),
+        }
+    }
+
+    /// Return compiled schema node, none for opaque node.
+    pub fn schema_node(&self) -> Option<LyscNode<'a>> {
+        LyscNode::from_ptr(self.schema())
+    }
+
+    /// Return schema node name.
+    pub fn schema_name(&self) -> &'a str {
+        let schema = self.schema();
+        unsafe {
+            let name = if schema.is_null() {
+                (*(self.node as *mut lyd_node_opaq)).name.name
+            } else {
+                (*schema).name
+            };
+            CStr::from_ptr(name).to_str().unwrap()
+        }
+    }
+
+    /// Return module name, none for opaque node.
+    pub fn module_name(&self) -> Option<&'a str> {
+        let schema = self.schema();
+        if schema.is_null() {
+            None
+        } else {
+            unsafe { Some(CStr::from_ptr((*(*schema).module).name).to_str().unwrap()) }
+        }
+    }
+
+    /// Return data path of the node.
+    pub fn path(&self) -> String {
+        unsafe {
+            let path = lyd_path(
+                self.node,
+                LYD_PATH_TYPE_LYD_PATH_STD,
+                std::ptr::null_mut(),
+                0,
+            );
+            let s = CStr::from_ptr(path).to_string_lossy().into_owned();
+            libc::free(path as *mut c_void);
+            s
+        }
+    }
+
+    /// Return canonical value of leaf or leaf-list, or value of opaque node.
+    ///  Value is copied, as a default leaf may be updated while the tree is shared.
+    pub fn value(&self) -> Option<String> {
+        let value = unsafe {
+            if self.is_opaque() {
+                (*(self.node as *mut lyd_node_opaq)).value
+            } else if self.is_term() {
+                lyd_value_get_canonical(self.ctx(), &(*(self.node as *mut lyd_node_term)).value)
+            } else {
+                std::ptr::null()
+            }
+        };
+
+        if value.is_null() {
+            None
+        } else {
+            unsafe { Some(CStr::from_ptr(value).to_string_lossy().into_owned()) }
+        }
+    }
+
+    /// Return key names and values of list instance, in schema order.
+    pub fn list_keys(&self) -> Vec<(String, String)> {
+        let mut keys = Vec::new();
+
+        if self.is_list() {
+            for child in self.children() {
+                let schema = child.schema();
+                if schema.is_null() || unsafe { (*schema).flags as u32 } & LYS_KEY == 0 {
+                    break;
+                }
+                keys.push((
+                    child.schema_name().to_string(),
+                    child.value().unwrap_or_default(),
+                ));
+            }
+        }
+
+        keys
+    }
+
+    /// Return true if the node was created as default one.
+    pub fn is_default(&self) -> bool {
+        unsafe { (*self.node).flags as u32 & LYD_DEFAULT != 0 }
+    }
+
+    /// Return iterator over metadata of this node.
+    pub fn meta(&self) -> LydMetaIter<'_> {
+        LydMetaIter {
+            ctx: self.ctx(),
+            next: unsafe { (*self.node).meta },
+            _node: PhantomData,
+        }
+    }
+
+    /// Find metadata by name in "module:name" form.
+    pub fn find_meta(&self, name: &str) -> Option<LydMeta<'_>> {
+        let name = CString::new(name).unwrap();
+        let meta = unsafe { lyd_find_meta((*self.node).meta, std::ptr::null(), name.as_ptr()) };
+
+        if meta.is_null() {
+            None
+        } else {
+            Some(LydMeta {
+                ctx: self.ctx(),
+                meta: meta,
+                _node: PhantomData,
+            })
+        }
+    }
+
+    /// Create metadata on this node, name is in "module:name" form if no module given.
+    ///  Default flag of the node is cleared when clear_dflt is set.
+    pub fn new_meta(
+        &self,
+        mod_name: Option<&str>,
+        name: &str,
+        value: &str,
+        clear_dflt: bool,
+    ) -> Result<LydMeta<'_>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let module = LibYang::lyd_new_module(&ctx, mod_name)?;
+        let name = CString::new(name).unwrap();
+        let value = CString::new(value).unwrap();
+        let mut meta: *mut lyd_meta = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe {
+            lyd_new_meta(
+                ctx.get_ctx(),
+                self.node,
+                module,
+                name.as_ptr(),
+                value.as_ptr(),
+                clear_dflt as ly_bool,
+                &mut meta,
+            )
+        };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(LydMeta {
+                ctx: self.ctx(),
+                meta: meta,
+                _node: PhantomData,
+            })
+        }
+    }
+
+    /// Return NMDA origin of the node, inherited from the nearest ancestor.
+    pub fn origin(&self) -> Option<String> {
+        let mut node = Some(*self);
+
+        while let Some(n) = node {
+            if let Some(meta) = n.find_meta("ietf-origin:origin") {
+                return meta.value().map(|v| v.to_string());
+            }
+            node = n.parent();
+        }
+
+        None
+    }
+
+    /// Create nodes by data path, relative to this node or absolute, with value of
+    ///  the last one. Returns the first created node, none if all of them existed.
+    ///  Existing nodes cannot be updated through shared node, see `LydNode::new_path`.
+    pub fn new_path(
+        &self,
+        path: &str,
+        value: Option<&LydValue>,
+        options: u32,
+    ) -> Result<Option<LydNodeRef<'a>>, i32> {
+        if options & LYD_NEW_PATH_UPDATE != 0 {
+            return Err(LY_ERR_LY_EINVAL as i32);
+        }
+        let node = LibYang::lyd_new_path_raw(self.node, std::ptr::null(), path, value, options)?;
+        Ok(self.opt_node_ref(node))
+    }
+
+    /// Create container, notification, RPC or action node under this node.
+    pub fn new_inner(
+        &self,
+        mod_name: Option<&str>,
+        name: &str,
+        output: bool,
+    ) -> Result<LydNodeRef<'a>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let node = LibYang::lyd_new_inner_raw(&ctx, self.node, mod_name, name, output)?;
+        Ok(self.node_ref(node))
+    }
+
+    /// Create leaf or leaf-list node under this node.
+    pub fn new_term(
+        &self,
+        mod_name: Option<&str>,
+        name: &str,
+        value: &str,
+        output: bool,
+    ) -> Result<LydNodeRef<'a>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let node = LibYang::lyd_new_term_raw(&ctx, self.node, mod_name, name, value, output)?;
+        Ok(self.node_ref(node))
+    }
+
+    /// Create list instance with given key names and values under this node.
+    pub fn new_list(
+        &self,
+        mod_name: Option<&str>,
+        name: &str,
+        keys: &[(&str, &str)],
+        output: bool,
+    ) -> Result<LydNodeRef<'a>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let node = LibYang::lyd_new_list_raw(&ctx, self.node, mod_name, name, keys, output)?;
+        Ok(self.node_ref(node))
+    }
+
+    /// Create anydata or anyxml node under this node, value is copied.
+    pub fn new_any(
+        &self,
+        mod_name: Option<&str>,
+        name: &str,
+        value: &LydValue,
+        output: bool,
+    ) -> Result<LydNodeRef<'a>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let node = LibYang::lyd_new_any_raw(&ctx, self.node, mod_name, name, value, output)?;
+        Ok(self.node_ref(node))
+    }
+
+    /// Create opaque node under this node.
+    pub fn new_opaq(
+        &self,
+        name: &str,
+        value: Option<&str>,
+        prefix: Option<&str>,
+        mod_name: &str,
+    ) -> Result<LydNodeRef<'a>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let node = LibYang::lyd_new_opaq_raw(&ctx, self.node, name, value, prefix, mod_name)?;
+        Ok(self.node_ref(node))
+    }
+
+    /// Find nodes matching XPath evaluated in the context of this node.
+    pub fn find_xpath(&self, xpath: &str) -> Result<LydNodeSet<'a>, LyError> {
+        self.find_xpath_vars(xpath, &[])
+    }
+
+    /// Find nodes matching XPath with variables evaluated in the context of this node.
+    pub fn find_xpath_vars(
+        &self,
+        xpath: &str,
+        vars: &[(&str, &str)],
+    ) -> Result<LydNodeSet<'a>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let xpath = CString::new(xpath).unwrap();
+        let mut lyxp_vars: *mut lyxp_var = std::ptr::null_mut();
+        let mut set: *mut ly_set = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        for (name, value) in vars {
+            let name = CString::new(*name).unwrap();
+            let value = CString::new(*value).unwrap();
+            let rc = unsafe { lyxp_vars_set(&mut lyxp_vars, name.as_ptr(), value.as_ptr()) };
+            if rc != LY_ERR_LY_SUCCESS {
+                unsafe {
+                    lyxp_vars_free(lyxp_vars);
+                }
+                return Err(ctx.take_errors(rc));
+            }
+        }
+
+        let rc = unsafe { lyd_find_xpath2(self.node, xpath.as_ptr(), lyxp_vars, &mut set) };
+        unsafe {
+            lyxp_vars_free(lyxp_vars);
+        }
+
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(LydNodeSet {
+                set: set,
+                owner: self.owner,
+            })
+        }
+    }
+
+    /// Evaluate XPath in the context of this node and return its boolean result.
+    pub fn eval_xpath(&self, xpath: &str) -> Result<bool, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let xpath = CString::new(xpath).unwrap();
+        let mut result: ly_bool = 0;
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_eval_xpath(self.node, xpath.as_ptr(), &mut result) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(result != 0)
+        }
+    }
+
+    /// Find single node by data path, relative to this node or absolute.
+    ///  Output nodes of RPC or action are searched when output is set.
+    pub fn find_path(&self, path: &str, output: bool) -> Result<Option<LydNodeRef<'a>>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let path = CString::new(path).unwrap();
+        let mut node: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_find_path(self.node, path.as_ptr(), output as ly_bool, &mut node) };
+        match rc {
+            LY_ERR_LY_SUCCESS => Ok(self.opt_node_ref(node)),
+            LY_ERR_LY_ENOTFOUND | LY_ERR_LY_EINCOMPLETE => {
+                ctx.clear_errors();
+                Ok(None)
+            }
+            _ => Err(ctx.take_errors(rc)),
+        }
+    }
+
+    /// Return diff of this subtree and other one, none if they are equal.
+    pub fn diff_tree(&self, other: LydNodeRef, opts: u32) -> Result<Option<LydNode>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let mut diff: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_diff_tree(self.node, other.node, opts as u16, &mut diff) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else if diff.is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(self.new_tree(diff)))
+        }
+    }
+
+    /// Return diff of this node with siblings and other ones, none if they are equal.
+    pub fn diff_siblings(&self, other: LydNodeRef, opts: u32) -> Result<Option<LydNode>, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let mut diff: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_diff_siblings(self.node, other.node, opts as u16, &mut diff) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else if diff.is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(self.new_tree(diff)))
+        }
+    }
+
+    /// Return diff operation of a diff tree node, i.e. create, delete, replace or none.
+    pub fn diff_operation(&self) -> Option<&'a str> {
+        let name = CString::new("yang:operation").unwrap();
+
+        unsafe {
+            let meta = lyd_find_meta((*self.node).meta, std::ptr::null(), name.as_ptr());
+            if meta.is_null() {
+                None
+            } else {
+                let value = lyd_value_get_canonical(self.ctx(), &(*meta).value);
+                Some(CStr::from_ptr(value).to_str().unwrap())
+            }
+        }
+    }
+
+    /// Duplicate this node, into given parent if any.
+    fn dup_raw(
+        &self,
+        parent: *mut lyd_node,
+        opts: u32,
+        siblings: bool,
+    ) -> Result<*mut lyd_node, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+        let parent = parent as *mut lyd_node_inner;
+        let mut dup: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe {
+            if siblings {
+                lyd_dup_siblings(self.node, parent, opts, &mut dup)
+            } else {
+                lyd_dup_single(self.node, parent, opts, &mut dup)
+            }
+        };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(dup)
+        }
+    }
+
+    /// Duplicate this node as new data tree.
+    pub fn dup_single(&self, opts: u32) -> Result<LydNode, LyError> {
+        let dup = self.dup_raw(std::ptr::null_mut(), opts, false)?;
+        Ok(self.new_tree(dup))
+    }
+
+    /// Duplicate this node into given parent.
+    pub fn dup_single_to<'b>(
+        &self,
+        parent: LydNodeRef<'b>,
+        opts: u32,
+    ) -> Result<LydNodeRef<'b>, LyError> {
+        let dup = self.dup_raw(parent.node, opts, false)?;
+        Ok(parent.node_ref(dup))
+    }
+
+    /// Duplicate this node with following siblings as new data tree.
+    pub fn dup_siblings(&self, opts: u32) -> Result<LydNode, LyError> {
+        let dup = self.dup_raw(std::ptr::null_mut(), opts, true)?;
+        Ok(self.new_tree(dup))
+    }
+
+    /// Duplicate this node with following siblings into given parent,
+    ///  and return the first duplicated one.
+    pub fn dup_siblings_to<'b>(
+        &self,
+        parent: LydNodeRef<'b>,
+        opts: u32,
+    ) -> Result<LydNodeRef<'b>, LyError> {
+        let dup = self.dup_raw(parent.node, opts, true)?;
+        Ok(parent.node_ref(dup))
+    }
+
+    /// Compare this node with other one, return true if they are equal.
+    pub fn compare_single(&self, other: LydNodeRef, opts: u32) -> Result<bool, LyError> {
+        let ctx = LibYangCtx::from(self.ctx());
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_compare_single(self.node, other.node, opts) };
+        match rc {
+            LY_ERR_LY_SUCCESS => Ok(true),
+            LY_ERR_LY_ENOT => Ok(false),
+            _ => Err(ctx.take_errors(rc)),
+        }
+    }
+
+    /// Print the node, or all its siblings with WithSiblings flag, into ly_out.
+    fn print_out(&self, out: *mut ly_out, format: LydFormat, opts: u32) -> Result<(), i32> {
+        let rc = unsafe {
+            if opts & LydPrintFlag::WithSiblings as u32 != 0 {
+                lyd_print_all(out, self.node, format as u32, opts)
+            } else {
+                lyd_print_tree(out, self.node, format as u32, opts)
+            }
+        };
+
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(rc as i32)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Print data tree in given format into memory.
+    pub fn print_mem(&self, format: LydFormat, opts: u32) -> Result<Vec<u8>, i32> {
+        let mut buf: *mut c_char = std::ptr::null_mut();
+        let mut out: *mut ly_out = std::ptr::null_mut();
+
+        let rc = unsafe { ly_out_new_memory(&mut buf, 0, &mut out) };
+        if rc != LY_ERR_LY_SUCCESS {
+            return Err(rc as i32);
+        }
+
+        let res = self.print_out(out, format, opts).map(|_| unsafe {
+            let len = ly_out_printed(out) as usize;
+            if buf.is_null() {
+                Vec::new()
+            } else {
+                slice::from_raw_parts(buf as *const u8, len).to_vec()
+            }
+        });
+
+        unsafe {
+            ly_out_free(out, None, 1);
+        }
+
+        res
+    }
+
+    /// Print data tree in XML or JSON format into string.
+    pub fn print_string(&self, format: LydFormat, opts: u32) -> Result<String, i32> {
+        let buf = self.print_mem(format, opts)?;
+
+        String::from_utf8(buf).map_err(|_| SrError::InvalArg as i32)
+    }
+
+    /// Print data tree in given format into writer.
+    pub fn print_writer<W: Write>(
+        &self,
+        writer: &mut W,
+        format: LydFormat,
+        opts: u32,
+    ) -> Result<(), i32> {
+        let mut out: *mut ly_out = std::ptr::null_mut();
+
+        let rc = unsafe {
+            ly_out_new_clb(
+                Some(LydNodeRef::call_write::<W>),
+                writer as *mut W as *mut c_void,
+                &mut out,
+            )
+        };
+        if rc != LY_ERR_LY_SUCCESS {
+            return Err(rc as i32);
+        }
+
+        let res = self.print_out(out, format, opts);
+
+        unsafe {
+            ly_out_free(out, None, 0);
+        }
+
+        res
+    }
+
+    unsafe extern "C" fn call_write<W: Write>(
+        user_data: *mut c_void,
+        buf: *const c_void,
+        count: size_t,
+    ) -> ssize_t {
+        let writer = &mut *(user_data as *mut W);
+        let buf = slice::from_raw_parts(buf as *const u8, count as usize);
+
+        match writer.write_all(buf) {
+            Ok(()) => count as ssize_t,
+            Err(_) => -1,
+        }
+    }
+}
+
+/// LibYang data node borrowed exclusively from the tree, to modify it.
+///  No other node of the tree can be borrowed while it exists.
+pub struct LydNodeMut<'a> {
+    /// Raw pointer to LibYang data node.
+    node: *mut lyd_node,
+
+    /// Owner of standalone context, borrowed from the tree.
+    owner: Option<&'a Rc<LyCtxOwner>>,
+
+    /// Tree the node is borrowed from.
+    _tree: PhantomData<&'a mut LydNode>,
+}
+
+impl<'a> LydNodeMut<'a> {
+    /// Borrow data node exclusively.
+    ///
+    /// # Safety
+    ///
+    /// The node must be valid and must not be freed for the lifetime of the reference,
+    ///  and no other reference to its tree may be used meanwhile.
+    pub unsafe fn from(node: *mut lyd_node) -> Self {
+        Self {
+            node: node,
+            owner: None,
+            _tree: PhantomData,
+        }
+    }
+
+    pub fn get_node(&self) -> *mut lyd_node {
+        self.node
+    }
+
+    /// Return the node borrowed for navigation and queries.
+    pub fn as_ref(&self) -> LydNodeRef<'_> {
+        LydNodeRef {
+            node: self.node,
+            owner: self.owner,
+        }
+    }
+
+    /// Remove metadata by name in "module:name" form, return true if found.
+    pub fn remove_meta(&mut self, name: &str) -> bool {
+        match self.as_ref().find_meta(name).map(|meta| meta.meta) {
+            Some(meta) => {
+                unsafe {
+                    lyd_free_meta_single(meta);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// LibYang metadata of data node.
+///  It just holds raw pointer, metadata are owned by the node it is borrowed from.
+pub struct LydMeta<'a> {
+    /// Raw pointer to context.
+    ctx: *const ly_ctx,
+
+    /// Raw pointer to metadata.
+    meta: *mut lyd_meta,
+
+    /// Node the metadata are borrowed from.
+    _node: PhantomData<&'a LydNodeRef<'a>>,
+}
+
+impl<'a> LydMeta<'a> {
+    pub fn get_meta(&self) -> *mut lyd_meta {
+        self.meta
+    }
+
+    /// Return metadata name.
+    pub fn name(&self) -> &'a str {
+        unsafe { CStr::from_ptr((*self.meta).name).to_str().unwrap() }
+    }
+
+    /// Return name of the module defining the annotation.
+    pub fn module_name(&self) -> Option<&'a str> {
+        unsafe {
+            let annotation = (*self.meta).annotation;
+            if annotation.is_null() || (*annotation).module.is_null() {
+                None
+            } else {
+                Some(
+                    CStr::from_ptr((*(*annotation).module).name)
+                        .to_str()
+                        .unwrap(),
+                )
+            }
+        }
+    }
+
+    /// Return canonical value.
+    pub fn value(&self) -> Option<&'a str> {
+        let value = unsafe { lyd_value_get_canonical(self.ctx, &(*self.meta).value) };
+
+        if value.is_null() {
+            None
+        } else {
+            unsafe { Some(CStr::from_ptr(value).to_str().unwrap()) }
+        }
+    }
+}
+
+/// LibYang metadata iterator.
+pub struct LydMetaIter<'a> {
+    /// Raw pointer to context.
+    ctx: *const ly_ctx,
+
+    /// Next metadata.
+    next: *mut lyd_meta,
+
+    /// Node the metadata are borrowed from.
+    _node: PhantomData<&'a LydNodeRef<'a>>,
+}
+
+impl<'a> Iterator for LydMetaIter<'a> {
+    type Item = LydMeta<'a>;
+
+    fn next(&mut self) -> Option<LydMeta<'a>> {
+        if self.next.is_null() {
+            None
+        } else {
+            let meta = LydMeta {
+                ctx: self.ctx,
+                meta: self.next,
+                _node: PhantomData,
+            };
+            self.next = unsafe { (*self.next).next };
+            Some(meta)
+        }
+    }
+}
+
+/// LibYang data node set, result of XPath query.
+///  Nodes are borrowed from the queried tree.
+pub struct LydNodeSet<'a> {
+    /// Raw pointer to set.
+    set: *mut ly_set,
+
+    /// Owner of standalone context, borrowed from the tree.
+    owner: Option<&'a Rc<LyCtxOwner>>,
+}
+
+impl<'a> LydNodeSet<'a> {
+    pub fn len(&self) -> usize {
+        unsafe { (*self.set).count as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return node at given index.
+    pub fn get(&self, index: usize) -> Option<LydNodeRef<'a>> {
+        if index >= self.len() {
+            None
+        } else {
+            Some(LydNodeRef {
+                node: unsafe { *(*self.set).__bindgen_anon_1.dnodes.add(index) },
+                owner: self.owner,
+            })
+        }
+    }
+
+    /// Return iterator over nodes.
+    pub fn iter(&self) -> LydNodeSetIter<'_, 'a> {
+        LydNodeSetIter {
+            set: self,
+            index: 0,
+        }
+    }
+
+    /// Return canonical values of all leaf and leaf-list nodes.
+    pub fn values(&self) -> Vec<String> {
+        self.iter()
+            .filter_map(|node| node.value().map(|v| v.to_string()))
+            .collect()
+    }
+}
+
+impl<'a> Drop for LydNodeSet<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            ly_set_free(self.set, None);
+        }
+    }
+}
+
+/// LibYang data node set iterator.
+pub struct LydNodeSetIter<'s, 'a> {
+    /// Node set.
+    set: &'s LydNodeSet<'a>,
+
+    /// Next index.
+    index: usize,
+}
+
+impl<'s, 'a> Iterator for LydNodeSetIter<'s, 'a> {
+    type Item = LydNodeRef<'a>;
+
+    fn next(&mut self) -> Option<LydNodeRef<'a>> {
+        let node = self.set.get(self.index)?;
+        self.index += 1;
+        Some(node)
+    }
+}
+
+/// LibYang data node sibling iterator.
+pub struct LydSiblingIter<'a> {
+    /// Next node.
+    next: Option<LydNodeRef<'a>>,
+}
+
+impl<'a> Iterator for LydSiblingIter<'a> {
+    type Item = LydNodeRef<'a>;
+
+    fn next(&mut self) -> Option<LydNodeRef<'a>> {
+        let node = self.next.take()?;
+        self.next = node.next_sibling();
+        Some(node)
+    }
+}
+
+/// LibYang data node depth-first iterator.
+pub struct LydTraverseIter<'a> {
+    /// Node the traversal started at.
+    start: LydNodeRef<'a>,
+
+    /// Next node.
+    next: Option<LydNodeRef<'a>>,
+}
+
+impl<'a> Iterator for LydTraverseIter<'a> {
+    type Item = LydNodeRef<'a>;
+
+    fn next(&mut self) -> Option<LydNodeRef<'a>> {
+        let node = self.next.take()?;
+
+        // Descend to child, or go to next sibling of nearest ancestor.
+        self.next = match node.first_child() {
+            Some(child) => Some(child),
+            None => {
+                let mut cur = node;
+                loop {
+                    if cur.get_node() == self.start.get_node() {
+                        break None;
+                    }
+                    if let Some(next) = cur.next_sibling() {
+                        break Some(next);
+                    }
+                    match cur.parent() {
+                        Some(parent) => cur = parent,
+                        None => break None,
+                    }
+                }
+            }
+        };
+
+        Some(node)
+    }
+}
+
+/// LibYang data value payload.
+enum LydValueData {
+    /// String, JSON or XML.
+    Str(CString),
+
+    /// Data tree.
+    Tree(LydNode),
+
+    /// LYB binary.
+    Lyb(Vec<u8>),
+}
+
+/// LibYang data value, used for terms and anydata or anyxml nodes.
+pub struct LydValue {
+    value_type: LydAnyDataValueType,
+
+    /// Payload according to value type.
+    value: LydValueData,
+}
+
+impl LydValue {
+    pub fn from_string(s: String) -> Self {
+        Self {
+            value_type: LydAnyDataValueType::String,
+            value: LydValueData::Str(CString::new(s).unwrap()),
+        }
+    }
+
+    pub fn from_json(s: String) -> Self {
+        Self {
+            value_type: LydAnyDataValueType::Json,
+            value: LydValueData::Str(CString::new(s).unwrap()),
+        }
+    }
+
+    pub fn from_xml(s: String) -> Self {
+        Self {
+            value_type: LydAnyDataValueType::Xml,
+            value: LydValueData::Str(CString::new(s).unwrap()),
+        }
+    }
+
+    /// Data tree is duplicated when the value is used.
+    pub fn from_datatree(tree: LydNode) -> Self {
+        Self {
+            value_type: LydAnyDataValueType::Datatree,
+            value: LydValueData::Tree(tree),
+        }
+    }
+
+    pub fn from_lyb(data: Vec<u8>) -> Self {
+        Self {
+            value_type: LydAnyDataValueType::Lyb,
+            value: LydValueData::Lyb(data),
+        }
+    }
+
+    /// Return string value, none for data tree and LYB.
+    pub fn get_value(&self) -> Option<&CStr> {
+        match &self.value {
+            LydValueData::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn get_value_raw(&self) -> *mut c_void {
+        match &self.value {
+            LydValueData::Str(s) => s.as_ptr() as *mut c_void,
+            LydValueData::Tree(tree) => tree.get_node() as *mut c_void,
+            LydValueData::Lyb(data) => data.as_ptr() as *mut c_void,
+        }
+    }
+
+    pub fn get_type(&self) -> LydAnyDataValueType {
         self.value_type
     }
 }
@@ -1112,33 +4943,860 @@ impl LydValue {
 pub struct LibYang {}
 
 impl LibYang {
+    /// Create new data tree by data path, with value of the last node.
     pub fn lyd_new_path(
-        parent: Option<&LydNode>,
-        ly_ctx: Option<&LibYangCtx>,
+        ly_ctx: &LibYangCtx,
         path: &str,
         value: Option<&LydValue>,
         options: u32,
     ) -> Result<LydNode, i32> {
-        let parent = parent.map_or(std::ptr::null_mut(), |parent| parent.get_node());
-        let ctx = ly_ctx.map_or(std::ptr::null_mut(), |ly_ctx| {
-            ly_ctx.get_ctx() as *mut ly_ctx
-        });
+        let node = LibYang::lyd_new_path_raw(
+            std::ptr::null_mut(),
+            ly_ctx.get_ctx(),
+            path,
+            value,
+            options,
+        )?;
+
+        if node.is_null() {
+            Err(LY_ERR_LY_EINVAL as i32)
+        } else {
+            Ok(ly_ctx.new_tree(node))
+        }
+    }
+
+    /// Create nodes by data path under parent if not null, and return the first one.
+    fn lyd_new_path_raw(
+        parent: *mut lyd_node,
+        ctx: *const ly_ctx,
+        path: &str,
+        value: Option<&LydValue>,
+        options: u32,
+    ) -> Result<*mut lyd_node, i32> {
         let path = CString::new(path).unwrap();
         let path = path.as_ptr() as *const _ as *const i8;
         let mut node: *mut lyd_node = unsafe { zeroed::<*mut lyd_node>() };
 
-
         let val = match value {
-            Some(value) => value.get_value().as_ptr(),
+            Some(value) => match value.get_value() {
+                Some(value) => value.as_ptr(),
+                None => return Err(LY_ERR_LY_EINVAL as i32),
+            },
             None => std::ptr::null_mut(),
         };
 
-        let rc = unsafe { lyd_new_path(parent, ctx, path, val, options, &mut node) };
+        let rc = unsafe { lyd_new_path(parent, ctx as *mut ly_ctx, path, val, options, &mut node) };
 
         if rc != LY_ERR_LY_SUCCESS {
             Err(rc as i32) // FIXME: We should not cast like this
         } else {
-            Ok(LydNode::from(node))
+            Ok(node)
+        }
+    }
+
+    /// Resolve implemented module for new node, null if no name is given.
+    fn lyd_new_module(
+        ctx: &LibYangCtx,
+        mod_name: Option<&str>,
+    ) -> Result<*const lys_module, LyError> {
+        match mod_name {
+            Some(mod_name) => {
+                let mod_name = CString::new(mod_name).unwrap();
+                let module =
+                    unsafe { ly_ctx_get_module_implemented(ctx.get_ctx(), mod_name.as_ptr()) };
+                if module.is_null() {
+                    Err(LyError::from_message(
+                        LY_ERR_LY_ENOTFOUND,
+                        format!("Module \"{}\" not found", mod_name.to_string_lossy()),
+                        None,
+                    ))
+                } else {
+                    Ok(module as *const lys_module)
+                }
+            }
+            None => Ok(std::ptr::null()),
+        }
+    }
+
+    /// Return result of node constructor.
+    fn lyd_new_result(
+        ctx: &LibYangCtx,
+        rc: LY_ERR,
+        node: *mut lyd_node,
+    ) -> Result<*mut lyd_node, LyError> {
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(node)
+        }
+    }
+
+    /// Create new data tree with container, notification, RPC or action node.
+    pub fn lyd_new_inner(
+        ly_ctx: &LibYangCtx,
+        mod_name: &str,
+        name: &str,
+        output: bool,
+    ) -> Result<LydNode, LyError> {
+        let node =
+            LibYang::lyd_new_inner_raw(ly_ctx, std::ptr::null_mut(), Some(mod_name), name, output)?;
+        Ok(ly_ctx.new_tree(node))
+    }
+
+    fn lyd_new_inner_raw(
+        ctx: &LibYangCtx,
+        parent: *mut lyd_node,
+        mod_name: Option<&str>,
+        name: &str,
+        output: bool,
+    ) -> Result<*mut lyd_node, LyError> {
+        let module = LibYang::lyd_new_module(ctx, mod_name)?;
+        let name = CString::new(name).unwrap();
+        let mut node: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc =
+            unsafe { lyd_new_inner(parent, module, name.as_ptr(), output as ly_bool, &mut node) };
+        LibYang::lyd_new_result(ctx, rc, node)
+    }
+
+    /// Create new data tree with leaf or leaf-list node.
+    pub fn lyd_new_term(
+        ly_ctx: &LibYangCtx,
+        mod_name: &str,
+        name: &str,
+        value: &str,
+        output: bool,
+    ) -> Result<LydNode, LyError> {
+        let node = LibYang::lyd_new_term_raw(
+            ly_ctx,
+            std::ptr::null_mut(),
+            Some(mod_name),
+            name,
+            value,
+            output,
+        )?;
+        Ok(ly_ctx.new_tree(node))
+    }
+
+    fn lyd_new_term_raw(
+        ctx: &LibYangCtx,
+        parent: *mut lyd_node,
+        mod_name: Option<&str>,
+        name: &str,
+        value: &str,
+        output: bool,
+    ) -> Result<*mut lyd_node, LyError> {
+        let module = LibYang::lyd_new_module(ctx, mod_name)?;
+        let name = CString::new(name).unwrap();
+        let value = CString::new(value).unwrap();
+        let mut node: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe {
+            lyd_new_term(
+                parent,
+                module,
+                name.as_ptr(),
+                value.as_ptr(),
+                output as ly_bool,
+                &mut node,
+            )
+        };
+        LibYang::lyd_new_result(ctx, rc, node)
+    }
+
+    /// Create new data tree with list instance with given key names and values.
+    pub fn lyd_new_list(
+        ly_ctx: &LibYangCtx,
+        mod_name: &str,
+        name: &str,
+        keys: &[(&str, &str)],
+        output: bool,
+    ) -> Result<LydNode, LyError> {
+        let node = LibYang::lyd_new_list_raw(
+            ly_ctx,
+            std::ptr::null_mut(),
+            Some(mod_name),
+            name,
+            keys,
+            output,
+        )?;
+        Ok(ly_ctx.new_tree(node))
+    }
+
+    fn lyd_new_list_raw(
+        ctx: &LibYangCtx,
+        parent: *mut lyd_node,
+        mod_name: Option<&str>,
+        name: &str,
+        keys: &[(&str, &str)],
+        output: bool,
+    ) -> Result<*mut lyd_node, LyError> {
+        let module = LibYang::lyd_new_module(ctx, mod_name)?;
+        let name = CString::new(name).unwrap();
+        let keys = keys
+            .iter()
+            .map(|(key, value)| Ok(format!("[{}={}]", key, xpath_quote(value)?)))
+            .collect::<Result<String, LyError>>()?;
+        let keys = CString::new(keys).unwrap();
+        let mut node: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe {
+            lyd_new_list2(
+                parent,
+                module,
+                name.as_ptr(),
+                keys.as_ptr(),
+                output as ly_bool,
+                &mut node,
+            )
+        };
+        LibYang::lyd_new_result(ctx, rc, node)
+    }
+
+    /// Create new data tree with anydata or anyxml node, value is copied.
+    pub fn lyd_new_any(
+        ly_ctx: &LibYangCtx,
+        mod_name: &str,
+        name: &str,
+        value: &LydValue,
+        output: bool,
+    ) -> Result<LydNode, LyError> {
+        let node = LibYang::lyd_new_any_raw(
+            ly_ctx,
+            std::ptr::null_mut(),
+            Some(mod_name),
+            name,
+            value,
+            output,
+        )?;
+        Ok(ly_ctx.new_tree(node))
+    }
+
+    fn lyd_new_any_raw(
+        ctx: &LibYangCtx,
+        parent: *mut lyd_node,
+        mod_name: Option<&str>,
+        name: &str,
+        value: &LydValue,
+        output: bool,
+    ) -> Result<*mut lyd_node, LyError> {
+        let module = LibYang::lyd_new_module(ctx, mod_name)?;
+        let name = CString::new(name).unwrap();
+        let mut node: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe {
+            lyd_new_any(
+                parent,
+                module,
+                name.as_ptr(),
+                value.get_value_raw(),
+                0,
+                value.get_type() as LYD_ANYDATA_VALUETYPE,
+                output as ly_bool,
+                &mut node,
+            )
+        };
+        LibYang::lyd_new_result(ctx, rc, node)
+    }
+
+    /// Create new data tree with opaque node, i.e. node without schema.
+    pub fn lyd_new_opaq(
+        ly_ctx: &LibYangCtx,
+        name: &str,
+        value: Option<&str>,
+        prefix: Option<&str>,
+        mod_name: &str,
+    ) -> Result<LydNode, LyError> {
+        let node =
+            LibYang::lyd_new_opaq_raw(ly_ctx, std::ptr::null_mut(), name, value, prefix, mod_name)?;
+        Ok(ly_ctx.new_tree(node))
+    }
+
+    fn lyd_new_opaq_raw(
+        ctx: &LibYangCtx,
+        parent: *mut lyd_node,
+        name: &str,
+        value: Option<&str>,
+        prefix: Option<&str>,
+        mod_name: &str,
+    ) -> Result<*mut lyd_node, LyError> {
+        let name = CString::new(name).unwrap();
+        let value = value.map(|value| CString::new(value).unwrap());
+        let prefix = prefix.map(|prefix| CString::new(prefix).unwrap());
+        let mod_name = CString::new(mod_name).unwrap();
+        let mut node: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe {
+            lyd_new_opaq(
+                parent,
+                ctx.get_ctx(),
+                name.as_ptr(),
+                value
+                    .as_ref()
+                    .map_or(std::ptr::null(), |value| value.as_ptr()),
+                prefix
+                    .as_ref()
+                    .map_or(std::ptr::null(), |prefix| prefix.as_ptr()),
+                mod_name.as_ptr(),
+                &mut node,
+            )
+        };
+        LibYang::lyd_new_result(ctx, rc, node)
+    }
+
+    /// Insert tree as last child of parent, and return its root node.
+    pub fn lyd_insert_child(parent: LydNodeRef, node: LydNode) -> Result<LydNodeRef, LyError> {
+        let ctx = LibYangCtx::from(parent.ctx());
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_insert_child(parent.get_node(), node.get_node()) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(parent.node_ref(node.into_inserted()))
+        }
+    }
+
+    /// Insert tree before sibling, and return its root node.
+    pub fn lyd_insert_before(sibling: LydNodeRef, node: LydNode) -> Result<LydNodeRef, LyError> {
+        let ctx = LibYangCtx::from(sibling.ctx());
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_insert_before(sibling.get_node(), node.get_node()) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(sibling.node_ref(node.into_inserted()))
+        }
+    }
+
+    /// Insert tree after sibling, and return its root node.
+    pub fn lyd_insert_after(sibling: LydNodeRef, node: LydNode) -> Result<LydNodeRef, LyError> {
+        let ctx = LibYangCtx::from(sibling.ctx());
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_insert_after(sibling.get_node(), node.get_node()) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            Ok(sibling.node_ref(node.into_inserted()))
+        }
+    }
+
+    /// Insert top-level nodes of tree among top-level siblings of other tree,
+    ///  and return the joined tree.
+    pub fn lyd_insert_sibling(sibling: LydNode, node: LydNode) -> Result<LydNode, LyError> {
+        let ctx = LibYangCtx::from(sibling.ctx);
+        let mut first: *mut lyd_node = std::ptr::null_mut();
+
+        ctx.clear_errors();
+        let rc = unsafe { lyd_insert_sibling(sibling.get_node(), node.get_node(), &mut first) };
+        if rc != LY_ERR_LY_SUCCESS {
+            Err(ctx.take_errors(rc))
+        } else {
+            let owner = sibling.owner.clone();
+            sibling.into_inserted();
+            node.into_inserted();
+            Ok(LydNode {
+                node: first,
+                ctx: ctx.get_ctx(),
+                owner: owner,
+            })
         }
     }
 }
+
+/// Split XPath into branches of top-level union, ignoring "|" in predicates,
+///  function arguments and literals.
+fn xpath_split_union(xpath: &str) -> Vec<&str> {
+    let mut branches = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+
+    for (i, c) in xpath.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (None, '[') | (None, '(') => depth += 1,
+            (None, ']') | (None, ')') => depth -= 1,
+            (None, '|') if depth == 0 => {
+                branches.push(xpath[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    branches.push(xpath[start..].trim());
+
+    branches
+}
+
+/// Quote value for XPath predicate, using double quotes if it contains single one.
+///  Value containing both cannot be quoted, as data paths do not support concat().
+fn xpath_quote(value: &str) -> Result<String, LyError> {
+    if !value.contains('\'') {
+        Ok(format!("'{}'", value))
+    } else if !value.contains('"') {
+        Ok(format!("\"{}\"", value))
+    } else {
+        Err(LyError::from_message(
+            LY_ERR_LY_EINVAL,
+            format!("Value {} contains both single and double quotes", value),
+            None,
+        ))
+    }
+}
+
+/// Return true if name is YANG identifier, optionally prefixed with module name.
+fn xpath_is_name(name: &str) -> bool {
+    let is_identifier = |s: &str| {
+        let mut chars = s.chars();
+        matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    };
+
+    match name.split_once(':') {
+        Some((module, name)) => is_identifier(module) && is_identifier(name),
+        None => is_identifier(name),
+    }
+}
+
+/// XPath predicate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum XPathPredicate {
+    /// List key name and value.
+    Key(String, String),
+
+    /// Leaf-list value.
+    Value(String),
+
+    /// Position of instance, starting at 1.
+    Position(u32),
+}
+
+impl XPathPredicate {
+    /// Return predicate with quoted value.
+    ///  Key name must be an identifier, and value must not contain both quote kinds.
+    pub fn to_xpath(&self) -> Result<String, LyError> {
+        match self {
+            XPathPredicate::Key(name, _) if !xpath_is_name(name) => Err(LyError::from_message(
+                LY_ERR_LY_EINVAL,
+                format!("Invalid key name \"{}\"", name),
+                None,
+            )),
+            XPathPredicate::Key(name, value) => Ok(format!("[{}={}]", name, xpath_quote(value)?)),
+            XPathPredicate::Value(value) => Ok(format!("[.={}]", xpath_quote(value)?)),
+            XPathPredicate::Position(position) => Ok(format!("[{}]", position)),
+        }
+    }
+}
+
+/// XPath location step.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct XPathStep {
+    /// Module name, inherited from previous step if not given, none only if
+    ///  there is no previous step.
+    pub module: Option<String>,
+
+    /// Node name, "*" for any node or "." for the node itself.
+    pub name: String,
+
+    /// Descendant axis, i.e. step follows "//".
+    pub descendant: bool,
+
+    /// Predicates.
+    pub predicates: Vec<XPathPredicate>,
+}
+
+impl XPathStep {
+    /// Return value of list key predicate.
+    pub fn key(&self, name: &str) -> Option<&str> {
+        self.predicates
+            .iter()
+            .find_map(|predicate| match predicate {
+                XPathPredicate::Key(key, value) if key == name => Some(value.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Return list key predicates as name and value pairs.
+    pub fn keys(&self) -> Vec<(&str, &str)> {
+        self.predicates
+            .iter()
+            .filter_map(|predicate| match predicate {
+                XPathPredicate::Key(key, value) => Some((key.as_str(), value.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// XPath builder.
+///  It composes absolute XPath of module qualified steps with quoted predicates,
+///  usable for get_items(), set_item_str() and subscriptions.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct XPathBuilder {
+    /// Location steps.
+    steps: Vec<XPathStep>,
+}
+
+impl XPathBuilder {
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    fn push(mut self, module: Option<&str>, name: &str, descendant: bool) -> Self {
+        let module = match module {
+            Some(module) => Some(module.to_string()),
+            None => self.steps.last().and_then(|step| step.module.clone()),
+        };
+        self.steps.push(XPathStep {
+            module: module,
+            name: name.to_string(),
+            descendant: descendant,
+            predicates: Vec::new(),
+        });
+        self
+    }
+
+    fn predicate(mut self, predicate: XPathPredicate) -> Self {
+        if let Some(step) = self.steps.last_mut() {
+            step.predicates.push(predicate);
+        }
+        self
+    }
+
+    /// Append step of node in module.
+    pub fn step(self, module: &str, name: &str) -> Self {
+        self.push(Some(module), name, false)
+    }
+
+    /// Append step of node in the same module as previous step.
+    pub fn child(self, name: &str) -> Self {
+        self.push(None, name, false)
+    }
+
+    /// Append step of descendant node in the same module as previous step, i.e. "//name".
+    pub fn descendant(self, name: &str) -> Self {
+        self.push(None, name, true)
+    }
+
+    /// Add list key predicate to last step.
+    pub fn key(self, name: &str, value: &str) -> Self {
+        self.predicate(XPathPredicate::Key(name.to_string(), value.to_string()))
+    }
+
+    /// Add leaf-list value predicate to last step.
+    pub fn value(self, value: &str) -> Self {
+        self.predicate(XPathPredicate::Value(value.to_string()))
+    }
+
+    /// Add position predicate to last step, starting at 1.
+    pub fn position(self, position: u32) -> Self {
+        self.predicate(XPathPredicate::Position(position))
+    }
+
+    /// Return location steps.
+    pub fn steps(&self) -> &[XPathStep] {
+        &self.steps
+    }
+
+    /// Return XPath, module name is given only where it changes.
+    ///  Fails if the first step has no module or a predicate cannot be quoted.
+    pub fn build(&self) -> Result<String, LyError> {
+        let mut xpath = String::new();
+        let mut current: Option<&str> = None;
+
+        for step in &self.steps {
+            let module = step.module.as_deref().ok_or_else(|| {
+                LyError::from_message(
+                    LY_ERR_LY_EINVAL,
+                    format!("Step \"{}\" has no module", step.name),
+                    None,
+                )
+            })?;
+
+            xpath.push_str(if step.descendant { "//" } else { "/" });
+            if current != Some(module) {
+                xpath.push_str(module);
+                xpath.push(':');
+                current = Some(module);
+            }
+            xpath.push_str(&step.name);
+            for predicate in &step.predicates {
+                xpath.push_str(&predicate.to_xpath()?);
+            }
+        }
+
+        Ok(xpath)
+    }
+
+    /// Parse absolute XPath into steps and predicates.
+    ///  Steps without module name inherit it from previous step.
+    pub fn parse(xpath: &str) -> Result<XPathBuilder, LyError> {
+        let chars: Vec<char> = xpath.chars().collect();
+        let error = |pos: usize, message: &str| {
+            LyError::from_message(
+                LY_ERR_LY_EINVAL,
+                format!("Invalid XPath \"{}\" at {}: {}", xpath, pos, message),
+                None,
+            )
+        };
+        let mut builder = XPathBuilder::new();
+        let mut pos = 0;
+
+        if chars.is_empty() {
+            return Err(error(0, "empty path"));
+        }
+
+        while pos < chars.len() {
+            if chars[pos] != '/' {
+                return Err(error(pos, "expected \"/\""));
+            }
+            pos += 1;
+            let descendant = chars.get(pos) == Some(&'/');
+            if descendant {
+                pos += 1;
+            }
+
+            let start = pos;
+            while pos < chars.len() && chars[pos] != '/' && chars[pos] != '[' {
+                pos += 1;
+            }
+            let token: String = chars[start..pos].iter().collect();
+            let token = token.trim();
+            if token.is_empty() {
+                return Err(error(start, "expected node name"));
+            }
+            let (module, name) = match token.split_once(':') {
+                Some((module, name)) => (module.to_string(), name.to_string()),
+                None => match builder.steps.last().and_then(|step| step.module.clone()) {
+                    Some(module) => (module, token.to_string()),
+                    None => return Err(error(start, "expected module name")),
+                },
+            };
+
+            let mut step = XPathStep {
+                module: Some(module),
+                name: name,
+                descendant: descendant,
+                predicates: Vec::new(),
+            };
+            while chars.get(pos) == Some(&'[') {
+                let (predicate, next) = xpath_parse_predicate(&chars, pos + 1)
+                    .map_err(|(pos, message)| error(pos, message))?;
+                step.predicates.push(predicate);
+                pos = next;
+            }
+            builder.steps.push(step);
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Return position of next non-space character.
+fn xpath_skip_spaces(chars: &[char], mut pos: usize) -> usize {
+    while pos < chars.len() && chars[pos].is_whitespace() {
+        pos += 1;
+    }
+    pos
+}
+
+/// Parse predicate after "[", and return it with position after "]".
+fn xpath_parse_predicate(
+    chars: &[char],
+    pos: usize,
+) -> Result<(XPathPredicate, usize), (usize, &'static str)> {
+    let start = xpath_skip_spaces(chars, pos);
+    let mut pos = start;
+
+    while pos < chars.len() && chars[pos] != '=' && chars[pos] != ']' {
+        pos += 1;
+    }
+    if pos >= chars.len() {
+        return Err((start, "unterminated predicate"));
+    }
+    let left: String = chars[start..pos].iter().collect();
+    let left = left.trim();
+
+    if chars[pos] == ']' {
+        return match left.parse::<u32>() {
+            Ok(position) => Ok((XPathPredicate::Position(position), pos + 1)),
+            Err(_) => Err((start, "expected position")),
+        };
+    }
+    if left != "." && !xpath_is_name(left) {
+        return Err((start, "invalid key name"));
+    }
+
+    let (value, pos) = xpath_parse_literal(chars, xpath_skip_spaces(chars, pos + 1))?;
+    let pos = xpath_skip_spaces(chars, pos);
+    if chars.get(pos) != Some(&']') {
+        return Err((pos, "expected \"]\""));
+    }
+
+    let predicate = if left == "." {
+        XPathPredicate::Value(value)
+    } else {
+        XPathPredicate::Key(left.to_string(), value)
+    };
+    Ok((predicate, pos + 1))
+}
+
+/// Parse quoted, concat() or unquoted literal, and return it with position after it.
+fn xpath_parse_literal(
+    chars: &[char],
+    pos: usize,
+) -> Result<(String, usize), (usize, &'static str)> {
+    match chars.get(pos) {
+        Some(&quote) if quote == '\'' || quote == '"' => {
+            match chars[pos + 1..].iter().position(|c| *c == quote) {
+                Some(len) => Ok((
+                    chars[pos + 1..pos + 1 + len].iter().collect(),
+                    pos + len + 2,
+                )),
+                None => Err((pos, "unterminated literal")),
+            }
+        }
+        Some(_) if chars[pos..].starts_with(&['c', 'o', 'n', 'c', 'a', 't', '(']) => {
+            let mut value = String::new();
+            let mut pos = pos + 7;
+            loop {
+                let (part, next) = xpath_parse_literal(chars, xpath_skip_spaces(chars, pos))?;
+                value.push_str(&part);
+                pos = xpath_skip_spaces(chars, next);
+                match chars.get(pos) {
+                    Some(',') => pos += 1,
+                    Some(')') => return Ok((value, pos + 1)),
+                    _ => return Err((pos, "expected \",\" or \")\"")),
+                }
+            }
+        }
+        Some(_) => {
+            let mut end = pos;
+            while end < chars.len() && chars[end] != ']' && !chars[end].is_whitespace() {
+                end += 1;
+            }
+            if end == pos {
+                Err((pos, "expected value"))
+            } else {
+                Ok((chars[pos..end].iter().collect(), end))
+            }
+        }
+        None => Err((pos, "expected value")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build XPath and parse it back.
+    fn round_trip(builder: XPathBuilder) -> String {
+        let xpath = builder.build().unwrap();
+        assert_eq!(XPathBuilder::parse(&xpath).unwrap(), builder);
+        xpath
+    }
+
+    #[test]
+    fn xpath_round_trip_quotes() {
+        let xpath = round_trip(
+            XPathBuilder::new()
+                .step("ietf-interfaces", "interfaces")
+                .child("interface")
+                .key("name", "it's")
+                .child("description")
+                .value("say \"hi\""),
+        );
+        assert_eq!(
+            xpath,
+            "/ietf-interfaces:interfaces/interface[name=\"it's\"]/description[.='say \"hi\"']"
+        );
+    }
+
+    #[test]
+    fn xpath_round_trip_slash_in_key() {
+        let xpath = round_trip(
+            XPathBuilder::new()
+                .step("ex", "routes")
+                .child("route")
+                .key("prefix", "10.0.0.0/8")
+                .key("ex:next-hop", "eth0/1[2]")
+                .child("metric"),
+        );
+        assert_eq!(
+            xpath,
+            "/ex:routes/route[prefix='10.0.0.0/8'][ex:next-hop='eth0/1[2]']/metric"
+        );
+    }
+
+    #[test]
+    fn xpath_round_trip_position() {
+        let xpath = round_trip(
+            XPathBuilder::new()
+                .step("ex", "list")
+                .position(2)
+                .descendant("leaf-list")
+                .position(1),
+        );
+        assert_eq!(xpath, "/ex:list[2]//leaf-list[1]");
+    }
+
+    #[test]
+    fn xpath_build_both_quotes() {
+        let builder = XPathBuilder::new()
+            .step("ex", "list")
+            .key("name", "it's \"quoted\"");
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn xpath_build_invalid_key_name() {
+        let builder = XPathBuilder::new()
+            .step("ex", "list")
+            .key("oper-status!", "up");
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn xpath_parse_invalid_key_name() {
+        assert!(XPathBuilder::parse("/ex:list[oper-status!='up']").is_err());
+        assert!(XPathBuilder::parse("/ex:list[1name='up']").is_err());
+        assert!(XPathBuilder::parse("/ex:list[ex:name='up']").is_ok());
+    }
+
+    #[test]
+    fn xpath_split_union_branches() {
+        assert_eq!(
+            xpath_split_union("/ex:a | /ex:b[name='x|y'][count(c|d)>1]"),
+            vec!["/ex:a", "/ex:b[name='x|y'][count(c|d)>1]"]
+        );
+        assert_eq!(xpath_split_union("/ex:a"), vec!["/ex:a"]);
+    }
+
+    #[test]
+    fn xpath_round_trip_repeated_module() {
+        let xpath = round_trip(
+            XPathBuilder::new()
+                .step("ex", "a")
+                .step("ex", "b")
+                .step("other", "c")
+                .child("d"),
+        );
+        assert_eq!(xpath, "/ex:a/b/other:c/d");
+    }
+
+    #[test]
+    fn xpath_first_step_without_module() {
+        assert!(XPathBuilder::new().child("a").build().is_err());
+        assert!(XPathBuilder::parse("/a/ex:b").is_err());
+    }
+}
@@ -0,0 +1,448 @@
+//! Conversion of serde types from and to data trees.
+//!
+//! Values are not mapped onto nodes directly, but go through RFC 7951 JSON: a value is
+//!  serialized to `serde_json::Value`, adjusted to the schema, printed and parsed by
+//!  libyang, and a tree is printed to JSON and adjusted back before deserializing.
+//!  Each conversion thus builds intermediate JSON values and text of the whole data,
+//!  so it costs roughly a print and a parse of the tree besides the serde work.
+//!
+//! Decimal64 values are decoded as strings in canonical form, to be held exactly in
+//!  string fields. Numbers are accepted too, printed with the fraction digits of the
+//!  type, so a floating point value is rounded to them.
+
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Number;
+use serde_json::Value;
+
+use crate::LibYangCtx;
+use crate::LyDataType;
+use crate::LyError;
+use crate::LydFormat;
+use crate::LydNode;
+use crate::LydParseFlag;
+use crate::LydPrintFlag;
+use crate::LydValidateFlag;
+use crate::LysModule;
+use crate::LysNodeType;
+use crate::LyscNode;
+use crate::LyscType;
+use crate::LY_ERR;
+use crate::LY_ERR_LY_EINVAL;
+use crate::LY_ERR_LY_ENOTFOUND;
+
+/// Serialize value as top-level data of module, and parse it into validated data tree.
+///  Field names are YANG node names, optionally module qualified, choices and cases
+///  are transparent, and empty leaves are booleans, with serde default if optional.
+pub fn to_node<T: Serialize>(
+    ctx: &LibYangCtx,
+    module: &str,
+    value: &T,
+) -> Result<Option<LydNode>, LyError> {
+    let json = to_rfc7951(ctx, module, value)?;
+    let data = serde_json::to_vec(&json).map_err(json_error)?;
+
+    ctx.parse_data(
+        &data,
+        LydFormat::Json,
+        LydParseFlag::Strict as u32,
+        LydValidateFlag::Present as u32,
+    )
+}
+
+/// Serialize value as top-level data of module, in RFC 7951 JSON encoding.
+pub fn to_rfc7951<T: Serialize>(
+    ctx: &LibYangCtx,
+    module: &str,
+    value: &T,
+) -> Result<Value, LyError> {
+    let module = get_module(ctx, module)?;
+    let siblings: Vec<LyscNode> = module.data_nodes().collect();
+
+    match serde_json::to_value(value).map_err(json_error)? {
+        Value::Object(map) => Ok(Value::Object(encode_siblings(&siblings, None, map)?)),
+        _ => Err(LyError::from_message(
+            LY_ERR_LY_EINVAL,
+            "Top-level value is not a struct or map".to_string(),
+            None,
+        )),
+    }
+}
+
+/// Deserialize value from top-level data of module in data tree.
+pub fn from_node<T: DeserializeOwned>(tree: &LydNode, module: &str) -> Result<T, LyError> {
+    let ctx = LibYangCtx::from(tree.ctx());
+    let json = match tree.as_ref() {
+        Some(root) => {
+            let data = root
+                .print_string(LydFormat::Json, LydPrintFlag::WithSiblings as u32)
+                .map_err(|rc| {
+                    LyError::from_message(
+                        rc as LY_ERR,
+                        "Failed to print data tree".to_string(),
+                        None,
+                    )
+                })?;
+            serde_json::from_str(&data).map_err(json_error)?
+        }
+        None => Value::Null,
+    };
+
+    from_rfc7951(&ctx, module, json)
+}
+
+/// Deserialize value from top-level data of module in RFC 7951 JSON encoding.
+///  Top-level nodes of other modules are ignored.
+pub fn from_rfc7951<T: DeserializeOwned>(
+    ctx: &LibYangCtx,
+    module: &str,
+    json: Value,
+) -> Result<T, LyError> {
+    let siblings: Vec<LyscNode> = get_module(ctx, module)?.data_nodes().collect();
+    let map = match json {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        _ => {
+            return Err(LyError::from_message(
+                LY_ERR_LY_EINVAL,
+                "Top-level value is not an object".to_string(),
+                None,
+            ))
+        }
+    };
+
+    let prefix = format!("{}:", module);
+    let map = map
+        .into_iter()
+        .filter(|(key, _)| key.starts_with(&prefix))
+        .collect();
+    let value = Value::Object(decode_siblings(&siblings, map)?);
+
+    serde_json::from_value(value).map_err(json_error)
+}
+
+/// Convert JSON error.
+fn json_error(err: serde_json::Error) -> LyError {
+    LyError::from_message(LY_ERR_LY_EINVAL, err.to_string(), None)
+}
+
+/// Return implemented module.
+fn get_module<'a>(ctx: &'a LibYangCtx, name: &str) -> Result<LysModule<'a>, LyError> {
+    ctx.get_module_implemented(name).ok_or_else(|| {
+        LyError::from_message(
+            LY_ERR_LY_ENOTFOUND,
+            format!("Module \"{}\" not found", name),
+            None,
+        )
+    })
+}
+
+/// Return error for value not matching schema node.
+fn mismatch(node: &LyscNode, expected: &str) -> LyError {
+    LyError::from_message(
+        LY_ERR_LY_EINVAL,
+        format!("Value of \"{}\" is not {}", node.name(), expected),
+        Some(node.path()),
+    )
+}
+
+/// Find schema node by name, optionally module qualified, looking through choices and cases.
+fn find_schema<'a>(siblings: &[LyscNode<'a>], key: &str) -> Option<LyscNode<'a>> {
+    let (module, name) = match key.split_once(':') {
+        Some((module, name)) => (Some(module), name),
+        None => (None, key),
+    };
+
+    for node in siblings {
+        match node.kind() {
+            LysNodeType::Choice | LysNodeType::Case => {
+                let children: Vec<LyscNode> = node.children().collect();
+                if let Some(found) = find_schema(&children, key) {
+                    return Some(found);
+                }
+            }
+            _ if node.name() == name
+                && (module.is_none() || module == Some(node.module().name())) =>
+            {
+                return Some(LyscNode {
+                    node: node.get_node(),
+                    _ctx: PhantomData,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Return true if type is encoded as string in JSON, even though it is numeric.
+fn is_string_number(ty: &LyscType, union: bool) -> bool {
+    match ty.base_type() {
+        LyDataType::Int64 | LyDataType::UInt64 | LyDataType::Decimal64 => true,
+        LyDataType::LeafRef => ty
+            .leafref_real_type()
+            .is_some_and(|real| is_string_number(&real, union)),
+        LyDataType::Union if union => ty
+            .union_types()
+            .iter()
+            .any(|member| is_string_number(member, union)),
+        _ => false,
+    }
+}
+
+/// Encode children of a node, qualifying names of nodes from other modules.
+fn encode_siblings(
+    siblings: &[LyscNode],
+    parent_module: Option<&str>,
+    map: Map<String, Value>,
+) -> Result<Map<String, Value>, LyError> {
+    let mut encoded = Map::new();
+
+    for (key, value) in map {
+        if value.is_null() {
+            continue;
+        }
+
+        let node = find_schema(siblings, &key).ok_or_else(|| {
+            LyError::from_message(
+                LY_ERR_LY_ENOTFOUND,
+                format!("Unknown node \"{}\"", key),
+                None,
+            )
+        })?;
+        let module = node.module().name().to_string();
+        let name = match parent_module {
+            Some(parent_module) if parent_module == module => node.name().to_string(),
+            _ => format!("{}:{}", module, node.name()),
+        };
+
+        if let Some(value) = encode_node(&node, &module, value)? {
+            encoded.insert(name, value);
+        }
+    }
+
+    Ok(encoded)
+}
+
+/// Encode value of a node, none if the node is not to be created.
+fn encode_node(node: &LyscNode, module: &str, value: Value) -> Result<Option<Value>, LyError> {
+    let children = || node.children().collect::<Vec<LyscNode>>();
+
+    match (node.kind(), value) {
+        (LysNodeType::Container, Value::Object(map)) => Ok(Some(Value::Object(encode_siblings(
+            &children(),
+            Some(module),
+            map,
+        )?))),
+        (LysNodeType::Container, _) => Err(mismatch(node, "an object")),
+        (LysNodeType::List, Value::Array(items)) => {
+            let children = children();
+            let items = items
+                .into_iter()
+                .map(|item| match item {
+                    Value::Object(map) => Ok(Value::Object(encode_siblings(
+                        &children,
+                        Some(module),
+                        map,
+                    )?)),
+                    _ => Err(mismatch(node, "an array of objects")),
+                })
+                .collect::<Result<Vec<Value>, LyError>>()?;
+            Ok(Some(Value::Array(items)))
+        }
+        (LysNodeType::List, _) => Err(mismatch(node, "an array")),
+        (LysNodeType::Leaf, value) => match node.leaf_type() {
+            Some(ty) => Ok(encode_leaf(&ty, value)),
+            None => Ok(Some(value)),
+        },
+        (LysNodeType::LeafList, Value::Array(items)) => match node.leaf_type() {
+            Some(ty) => Ok(Some(Value::Array(
+                items
+                    .into_iter()
+                    .filter_map(|item| encode_leaf(&ty, item))
+                    .collect(),
+            ))),
+            None => Ok(Some(Value::Array(items))),
+        },
+        (LysNodeType::LeafList, _) => Err(mismatch(node, "an array")),
+        (_, value) => Ok(Some(value)),
+    }
+}
+
+/// Encode leaf value, empty leaf is "[null]" if true and omitted if false.
+fn encode_leaf(ty: &LyscType, value: Value) -> Option<Value> {
+    match (ty.base_type(), value) {
+        (LyDataType::Empty, Value::Bool(false)) => None,
+        (LyDataType::Empty, _) => Some(Value::Array(vec![Value::Null])),
+        (_, Value::Number(n)) if is_string_number(ty, true) => {
+            Some(Value::String(number_string(ty, &n)))
+        }
+        (_, value) => Some(value),
+    }
+}
+
+/// Return fraction digits of decimal64 type, looking through leafrefs and union members.
+fn fraction_digits(ty: &LyscType) -> Option<u8> {
+    match ty.base_type() {
+        LyDataType::Decimal64 => ty.fraction_digits(),
+        LyDataType::LeafRef => ty
+            .leafref_real_type()
+            .and_then(|real| fraction_digits(&real)),
+        LyDataType::Union => ty.union_types().iter().find_map(fraction_digits),
+        _ => None,
+    }
+}
+
+/// Format number as string, with fraction digits of decimal64 type. Integers in unions
+///  are kept as they are, since they may be meant for an integer member.
+fn number_string(ty: &LyscType, n: &Number) -> String {
+    match (fraction_digits(ty), n.as_f64()) {
+        (Some(digits), Some(f)) if n.is_f64() => format!("{:.*}", digits as usize, f),
+        (Some(digits), _) if ty.base_type() != LyDataType::Union => {
+            format!("{}.{}", n, "0".repeat(digits as usize))
+        }
+        _ => n.to_string(),
+    }
+}
+
+/// Decode children of a node, stripping module names.
+fn decode_siblings(
+    siblings: &[LyscNode],
+    map: Map<String, Value>,
+) -> Result<Map<String, Value>, LyError> {
+    let mut decoded = Map::new();
+
+    for (key, value) in map {
+        let node = find_schema(siblings, &key).ok_or_else(|| {
+            LyError::from_message(
+                LY_ERR_LY_ENOTFOUND,
+                format!("Unknown node \"{}\"", key),
+                None,
+            )
+        })?;
+        decoded.insert(node.name().to_string(), decode_node(&node, value)?);
+    }
+
+    Ok(decoded)
+}
+
+/// Decode value of a node.
+fn decode_node(node: &LyscNode, value: Value) -> Result<Value, LyError> {
+    let children = || node.children().collect::<Vec<LyscNode>>();
+
+    match (node.kind(), value) {
+        (LysNodeType::Container, Value::Object(map)) => {
+            Ok(Value::Object(decode_siblings(&children(), map)?))
+        }
+        (LysNodeType::List, Value::Array(items)) => {
+            let children = children();
+            let items = items
+                .into_iter()
+                .map(|item| match item {
+                    Value::Object(map) => Ok(Value::Object(decode_siblings(&children, map)?)),
+                    item => Ok(item),
+                })
+                .collect::<Result<Vec<Value>, LyError>>()?;
+            Ok(Value::Array(items))
+        }
+        (LysNodeType::Leaf, value) => match node.leaf_type() {
+            Some(ty) => Ok(decode_leaf(&ty, value)),
+            None => Ok(value),
+        },
+        (LysNodeType::LeafList, Value::Array(items)) => match node.leaf_type() {
+            Some(ty) => Ok(Value::Array(
+                items
+                    .into_iter()
+                    .map(|item| decode_leaf(&ty, item))
+                    .collect(),
+            )),
+            None => Ok(Value::Array(items)),
+        },
+        (_, value) => Ok(value),
+    }
+}
+
+/// Decode leaf value, empty leaf is true and 64-bit integers are converted from strings.
+///  Decimal64 is kept as string, as floating point cannot hold it exactly.
+fn decode_leaf(ty: &LyscType, value: Value) -> Value {
+    match (ty.base_type(), value) {
+        (LyDataType::Empty, _) => Value::Bool(true),
+        (_, Value::String(s)) if is_string_number(ty, false) && fraction_digits(ty).is_none() => {
+            if let Ok(n) = s.parse::<i64>() {
+                Value::Number(n.into())
+            } else if let Ok(n) = s.parse::<u64>() {
+                Value::Number(n.into())
+            } else {
+                Value::String(s)
+            }
+        }
+        (_, value) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::LysInFormat;
+
+    const MODULE: &str = r#"
+        module ex {
+            yang-version 1.1;
+            namespace "urn:ex";
+            prefix ex;
+
+            container stats {
+                leaf ratio {
+                    type decimal64 {
+                        fraction-digits 2;
+                    }
+                }
+                leaf total {
+                    type int64;
+                }
+            }
+        }
+    "#;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Stats {
+        ratio: Option<String>,
+        total: Option<i64>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Data {
+        stats: Option<Stats>,
+    }
+
+    #[test]
+    fn round_trip_decimal64_and_int64() {
+        let ctx = LibYangCtx::new(&[], 0).unwrap();
+        ctx.parse_module_mem(MODULE, LysInFormat::Yang, &[])
+            .unwrap();
+
+        let data = Data {
+            stats: Some(Stats {
+                ratio: Some("1.5".to_string()),
+                total: Some(9007199254740993),
+            }),
+        };
+        let tree = to_node(&ctx, "ex", &data).unwrap().unwrap();
+        let ratio = tree
+            .as_ref()
+            .unwrap()
+            .find_path("/ex:stats/ratio", false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ratio.value().unwrap(), "1.5");
+
+        let decoded: Data = from_node(&tree, "ex").unwrap();
+        assert_eq!(decoded, data);
+    }
+}